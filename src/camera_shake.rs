@@ -0,0 +1,127 @@
+use crate::collision::{DamageDealt, Team};
+use crate::noise::value_noise_1d;
+use bevy::prelude::*;
+
+/// Trauma-based screen shake: events add trauma, which decays over time.
+/// The camera shakes with the square of the trauma, so small bumps stay subtle.
+#[derive(Resource)]
+pub struct CameraShake {
+    /// Current trauma, from 0 (still) to 1 (maximum shake)
+    pub trauma: f32,
+    /// Trauma lost per second
+    pub decay: f32,
+    /// Maximum camera offset in world units, at full trauma
+    pub max_offset: f32,
+    /// Maximum camera roll in radians, at full trauma
+    pub max_rotation: f32,
+    /// How fast the camera shakes
+    pub frequency: f32,
+    /// Accessibility setting, no shake at all when disabled
+    pub enabled: bool,
+    pub time: f32,
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        Self {
+            trauma: 0.0,
+            decay: 1.5,
+            max_offset: 0.15,
+            max_rotation: 0.05,
+            frequency: 15.0,
+            enabled: true,
+            time: 0.0,
+        }
+    }
+}
+
+impl CameraShake {
+    pub fn add_trauma(&mut self, amount: f32) {
+        if self.enabled {
+            self.trauma = (self.trauma + amount).min(1.0);
+        }
+    }
+}
+
+/// The resting transform of a camera shaken by CameraShake
+#[derive(Component, Clone, Copy)]
+pub struct CameraRig {
+    pub base: Transform,
+}
+
+/// Smooth, non-repeating noise between -1 and 1, different for every seed
+fn shake_noise(time: f32, seed: u32) -> f32 {
+    value_noise_1d(time, seed) * 2.0 - 1.0
+}
+
+/// Offset the camera from its resting transform according to the trauma
+pub fn apply_camera_shake(
+    mut cameras: Query<(&mut Transform, &CameraRig)>,
+    mut camera_shake: ResMut<CameraShake>,
+    time: Res<Time>,
+) {
+    camera_shake.trauma = (camera_shake.trauma - camera_shake.decay * time.delta_secs()).max(0.0);
+    camera_shake.time += time.delta_secs() * camera_shake.frequency;
+
+    let shake = camera_shake.trauma * camera_shake.trauma;
+    let offset = Vec3::new(
+        shake_noise(camera_shake.time, 1),
+        shake_noise(camera_shake.time, 2),
+        0.0,
+    ) * camera_shake.max_offset
+        * shake;
+    let roll = shake_noise(camera_shake.time, 3) * camera_shake.max_rotation * shake;
+
+    for (mut transform, rig) in cameras.iter_mut() {
+        // Shake in the camera's own plane
+        transform.translation = rig.base.translation + rig.base.rotation * offset;
+        transform.rotation = rig.base.rotation * Quat::from_rotation_z(roll);
+    }
+}
+
+/// Shake the camera when the ship is hit, harder for bigger hits
+pub fn shake_on_player_damage(
+    mut damage_dealt: MessageReader<DamageDealt>,
+    mut camera_shake: ResMut<CameraShake>,
+) {
+    for damage in damage_dealt.read() {
+        if damage.team == Team::Player {
+            camera_shake.add_trauma((damage.amount / 50.0).clamp(0.2, 0.6));
+        }
+    }
+}
+
+/// Toggle screen shake with K
+pub fn toggle_camera_shake(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut camera_shake: ResMut<CameraShake>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyK) {
+        camera_shake.enabled = !camera_shake.enabled;
+        camera_shake.trauma = 0.0;
+        info!(
+            "Screen shake {}",
+            if camera_shake.enabled {
+                "enabled"
+            } else {
+                "disabled"
+            }
+        );
+    }
+}
+
+pub struct CameraShakePlugin;
+
+impl Plugin for CameraShakePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraShake>().add_systems(
+            Update,
+            (
+                toggle_camera_shake,
+                shake_on_player_damage,
+                apply_camera_shake,
+            )
+                .chain(),
+        );
+    }
+}
@@ -1,3 +1,9 @@
+use crate::camera_shake::CameraShake;
+use crate::particles::{ParticleBurst, ParticleEmitter};
+use crate::pool::{EntityPool, Pooled, despawn_or_release};
+use crate::projectiles::Projectile;
+use crate::projectiles::modifiers::{Piercing, Ricochet, absorb_hit, ignores_target};
+use bevy::ecs::query::{QueryData, QueryFilter};
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
@@ -19,6 +25,18 @@ pub struct Collidable {
     pub hit_points: f32,
     /// The team this entity belongs to
     pub team: Team,
+    /// Invulnerable entities still collide and deal damage, but take none
+    pub invulnerable: bool,
+}
+
+/// Sent when a collision takes hit points from a collidable, projectiles excluded
+#[derive(Message, Clone, Copy, Debug)]
+pub struct DamageDealt {
+    /// Where the hit landed
+    pub position: Vec3,
+    pub amount: f32,
+    /// Team of the damaged entity
+    pub team: Team,
 }
 
 /// A marker component for entities that should not be automatically despawned when they die
@@ -33,6 +51,7 @@ impl Collidable {
             max_hit_points,
             hit_points: max_hit_points,
             team,
+            invulnerable: false,
         }
     }
 
@@ -43,6 +62,9 @@ impl Collidable {
 
     /// Apply damage to this entity
     pub fn take_damage(&mut self, damage: f32) {
+        if self.invulnerable {
+            return;
+        }
         self.hit_points -= damage;
         if self.hit_points < 0.0 {
             self.hit_points = 0.0;
@@ -50,11 +72,36 @@ impl Collidable {
     }
 }
 
+/// Apply damage to every collidable of another team within the radius, except the excluded
+/// entities (the source itself, and anything it already damaged directly)
+pub fn apply_area_damage(
+    center: Vec3,
+    radius: f32,
+    damage: f32,
+    team: Team,
+    excluded: &[Entity],
+    targets: &mut Query<(Entity, &GlobalTransform, &mut Collidable)>,
+) {
+    for (entity, transform, mut collidable) in targets.iter_mut() {
+        if excluded.contains(&entity) || collidable.team == team {
+            continue;
+        }
+        if transform.translation().distance(center) <= radius {
+            collidable.take_damage(damage);
+        }
+    }
+}
+
 /// Check for collisions using Rapier collision events
 pub fn handle_collision_events(
     mut collision_events: MessageReader<CollisionEvent>,
     mut collidables: Query<&mut Collidable>,
+    mut piercings: Query<&mut Piercing>,
+    mut ricochets: Query<&mut Ricochet>,
+    transforms: Query<&GlobalTransform>,
     parents: Query<&ChildOf>,
+    projectiles: Query<(), With<Projectile>>,
+    mut damage_dealt: MessageWriter<DamageDealt>,
 ) {
     for collision_event in collision_events.read() {
         if let CollisionEvent::Started(e1, e2, _) = collision_event {
@@ -73,24 +120,114 @@ pub fn handle_collision_events(
                     if coll_a.team == coll_b.team {
                         continue;
                     }
+                    // Skip targets a piercing or ricochet projectile already went through
+                    if ignores_target(entity_a, entity_b, &piercings, &ricochets)
+                        || ignores_target(entity_b, entity_a, &piercings, &ricochets)
+                    {
+                        continue;
+                    }
                     println!(
                         "Collision between Team::{:?} and Team::{:?}",
                         coll_a.team, coll_b.team
                     );
                     println!("entity_a: {:?}, entity_b: {:?}", entity_a, entity_b);
 
+                    let hit_position =
+                        hit_location(*e1, *e2, entity_a, entity_b, &transforms, &projectiles);
+
                     // Apply damage to both entities based on the other's damage
                     let damage_a = coll_a.damage;
                     let damage_b = coll_b.damage;
 
-                    coll_a.take_damage(damage_b);
-                    coll_b.take_damage(damage_a);
+                    // Projectile modifiers may let a projectile survive the hit
+                    if !absorb_hit(
+                        entity_a,
+                        entity_b,
+                        &mut piercings,
+                        &mut ricochets,
+                        &transforms,
+                    ) {
+                        let hit_points_before = coll_a.hit_points;
+                        coll_a.take_damage(damage_b);
+                        report_damage(
+                            entity_a,
+                            &coll_a,
+                            hit_points_before,
+                            hit_position,
+                            &projectiles,
+                            &mut damage_dealt,
+                        );
+                    }
+                    if !absorb_hit(
+                        entity_b,
+                        entity_a,
+                        &mut piercings,
+                        &mut ricochets,
+                        &transforms,
+                    ) {
+                        let hit_points_before = coll_b.hit_points;
+                        coll_b.take_damage(damage_a);
+                        report_damage(
+                            entity_b,
+                            &coll_b,
+                            hit_points_before,
+                            hit_position,
+                            &projectiles,
+                            &mut damage_dealt,
+                        );
+                    }
                 }
             }
         }
     }
 }
 
+/// Where a collision happened: the projectile's position when a projectile hit something,
+/// otherwise halfway between the two colliders that touched
+fn hit_location(
+    collider_a: Entity,
+    collider_b: Entity,
+    entity_a: Entity,
+    entity_b: Entity,
+    transforms: &Query<&GlobalTransform>,
+    projectiles: &Query<(), With<Projectile>>,
+) -> Option<Vec3> {
+    let position_a = transforms.get(collider_a).ok()?.translation();
+    let position_b = transforms.get(collider_b).ok()?.translation();
+    Some(
+        match (
+            projectiles.contains(entity_a),
+            projectiles.contains(entity_b),
+        ) {
+            (true, false) => position_a,
+            (false, true) => position_b,
+            _ => (position_a + position_b) / 2.0,
+        },
+    )
+}
+
+/// Send a DamageDealt message if the collidable lost hit points, projectiles are not reported
+fn report_damage(
+    entity: Entity,
+    collidable: &Collidable,
+    hit_points_before: f32,
+    hit_position: Option<Vec3>,
+    projectiles: &Query<(), With<Projectile>>,
+    damage_dealt: &mut MessageWriter<DamageDealt>,
+) {
+    let amount = hit_points_before - collidable.hit_points;
+    if amount <= 0.0 || projectiles.contains(entity) {
+        return;
+    }
+    if let Some(position) = hit_position {
+        damage_dealt.write(DamageDealt {
+            position,
+            amount,
+            team: collidable.team,
+        });
+    }
+}
+
 /// Propagates physics settings from parent to children with colliders.
 /// This is useful when using AsyncSceneCollider, as it creates colliders on children
 /// but doesn't automatically copy ActiveEvents or ActiveCollisionTypes from the parent.
@@ -121,10 +258,13 @@ pub fn propagate_physics_settings(
     }
 }
 
-/// Helper function to find the ancestor entity that has the Collidable component
-fn find_collidable_root(
+/// Helper function to find the nearest ancestor entity that has the Collidable component.
+/// Entities made of several collidable parts (like bosses) give each part its own collider,
+/// so a hit is routed to the part that was struck rather than to the root entity.
+/// `collidables` can be any query matching exactly the entities with a Collidable.
+pub(crate) fn find_collidable_root<D: QueryData, F: QueryFilter>(
     entity: Entity,
-    collidables: &Query<&mut Collidable>,
+    collidables: &Query<D, F>,
     parents: &Query<&ChildOf>,
 ) -> Option<Entity> {
     let mut current = entity;
@@ -143,11 +283,31 @@ fn find_collidable_root(
 /// Despawn any collidable entities that have died (hit_points <= 0)
 pub fn despawn_dead_collidable(
     mut commands: Commands,
-    collidables: Query<(Entity, &Collidable), Without<Persistent>>,
+    mut pool: ResMut<EntityPool>,
+    collidables: Query<
+        (
+            Entity,
+            &Collidable,
+            &GlobalTransform,
+            Option<&Pooled>,
+            Has<Projectile>,
+        ),
+        Without<Persistent>,
+    >,
+    mut bursts: MessageWriter<ParticleBurst>,
+    mut camera_shake: ResMut<CameraShake>,
 ) {
-    for (entity, collidable) in collidables.iter() {
+    for (entity, collidable, transform, pooled, is_projectile) in collidables.iter() {
         if !collidable.is_alive() {
-            commands.entity(entity).despawn();
+            // Spent projectiles already threw sparks on impact, anything else blows up
+            if !is_projectile {
+                bursts.write(ParticleBurst::new(
+                    transform.translation(),
+                    ParticleEmitter::explosion(),
+                ));
+                camera_shake.add_trauma(0.15);
+            }
+            despawn_or_release(&mut commands, &mut pool, entity, pooled);
         }
     }
 }
@@ -157,7 +317,7 @@ pub struct CollisionPlugin;
 
 impl Plugin for CollisionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.add_message::<DamageDealt>().add_systems(
             Update,
             (
                 handle_collision_events,
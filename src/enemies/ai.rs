@@ -0,0 +1,218 @@
+use bevy::prelude::*;
+
+/// Result of evaluating a behaviour tree node
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Status {
+    Success,
+    Failure,
+    Running, // The node is acting and wants to keep acting next frame
+}
+
+/// A check on the blackboard, succeeding or failing without acting
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Condition {
+    /// Hit points below this fraction of the maximum
+    HealthBelow(f32),
+    /// The ship is within this distance
+    ShipWithin(f32),
+}
+
+/// Something an enemy does, by writing its wishes to the blackboard
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Action {
+    /// Keep following the enemy's movement pattern, or its current velocity
+    Patrol,
+    /// Fly toward the ship
+    Approach { speed: f32 },
+    /// Fly sideways relative to the ship, switching side every few seconds
+    Strafe { speed: f32 },
+    /// Back away from the ship while facing it, slowing down to hold at `distance`
+    Retreat { speed: f32, distance: f32 },
+    /// Fire the enemy's weapon, succeeds immediately so a sequence can also move
+    Attack,
+    /// Run away from the ship as fast as possible, turning back to the resting heading
+    Flee { speed: f32 },
+}
+
+/// A node of a behaviour tree
+#[derive(Clone, Debug)]
+pub enum BehaviorNode {
+    /// Runs children in order until one does not fail
+    Selector(Vec<BehaviorNode>),
+    /// Runs children in order until one does not succeed
+    Sequence(Vec<BehaviorNode>),
+    Condition(Condition),
+    Action(Action),
+}
+
+/// Per-enemy data shared by the nodes of its behaviour tree.
+/// `enemy_behavior` fills in the inputs before each evaluation and applies the outputs after.
+#[derive(Clone, Default, Debug)]
+pub struct Blackboard {
+    // Inputs
+    pub position: Vec3,
+    pub health_ratio: f32,
+    pub ship_position: Option<Vec3>,
+    pub age: f32, // Time since the enemy spawned
+    // Outputs
+    pub desired_velocity: Option<Vec3>, // None keeps the current movement
+    pub facing: Option<Vec3>,           // None turns back to the resting heading
+    pub wants_to_fire: bool,
+}
+
+impl Blackboard {
+    /// Direction from the enemy to the ship, if the ship is known and not on top of the enemy
+    pub fn ship_direction(&self) -> Option<Vec3> {
+        self.ship_position
+            .and_then(|ship_position| (ship_position - self.position).try_normalize())
+    }
+}
+
+impl BehaviorNode {
+    pub fn tick(&self, blackboard: &mut Blackboard) -> Status {
+        match self {
+            BehaviorNode::Selector(children) => children
+                .iter()
+                .map(|child| child.tick(blackboard))
+                .find(|status| *status != Status::Failure)
+                .unwrap_or(Status::Failure),
+            BehaviorNode::Sequence(children) => children
+                .iter()
+                .map(|child| child.tick(blackboard))
+                .find(|status| *status != Status::Success)
+                .unwrap_or(Status::Success),
+            BehaviorNode::Condition(condition) => {
+                let passed = match condition {
+                    Condition::HealthBelow(ratio) => blackboard.health_ratio < *ratio,
+                    Condition::ShipWithin(distance) => blackboard
+                        .ship_position
+                        .is_some_and(|ship| ship.distance(blackboard.position) <= *distance),
+                };
+                if passed {
+                    Status::Success
+                } else {
+                    Status::Failure
+                }
+            }
+            BehaviorNode::Action(action) => run_action(*action, blackboard),
+        }
+    }
+}
+
+fn run_action(action: Action, blackboard: &mut Blackboard) -> Status {
+    let velocity = match action {
+        Action::Patrol => None,
+        Action::Attack => {
+            blackboard.wants_to_fire = true;
+            return Status::Success;
+        }
+        Action::Approach { speed } => blackboard
+            .ship_direction()
+            .map(|direction| direction * speed),
+        Action::Retreat { speed, distance } => blackboard.ship_direction().map(|direction| {
+            blackboard.facing = Some(direction);
+            let gap = blackboard
+                .ship_position
+                .map_or(distance, |ship| ship.distance(blackboard.position));
+            // Full speed until the last quarter of the way, then ease into holding position
+            let slowdown = ((distance - gap) / (0.25 * distance)).clamp(0.0, 1.0);
+            -direction * speed * slowdown
+        }),
+        Action::Flee { speed } => blackboard
+            .ship_direction()
+            .map(|direction| -direction * speed),
+        Action::Strafe { speed } => blackboard.ship_direction().map(|direction| {
+            // Perpendicular to the ship direction in the play plane
+            let side = if (blackboard.age * 0.5).sin() >= 0.0 {
+                1.0
+            } else {
+                -1.0
+            };
+            direction.cross(Vec3::Z) * side * speed
+        }),
+    };
+
+    // Every action but patrolling needs to know where the ship is
+    if velocity.is_none() && action != Action::Patrol {
+        return Status::Failure;
+    }
+
+    blackboard.desired_velocity = velocity;
+    Status::Running
+}
+
+/// A behaviour tree with its blackboard, evaluated every frame by `enemy_behavior`
+#[derive(Component, Clone)]
+pub struct BehaviorTree {
+    pub root: BehaviorNode,
+    pub blackboard: Blackboard,
+    /// Rotation of the model facing -X, the way enemies enter the screen.
+    /// Enemies without one never turn to face anything.
+    pub model_rotation: Option<Quat>,
+}
+
+impl BehaviorTree {
+    pub fn new(root: BehaviorNode) -> Self {
+        Self {
+            root,
+            blackboard: Blackboard::default(),
+            model_rotation: None,
+        }
+    }
+
+    pub fn with_model_rotation(mut self, model_rotation: Quat) -> Self {
+        self.model_rotation = Some(model_rotation);
+        self
+    }
+
+    /// Evaluate the tree, the outputs of the previous evaluation are cleared first
+    pub fn tick(&mut self) -> Status {
+        self.blackboard.desired_velocity = None;
+        self.blackboard.facing = None;
+        self.blackboard.wants_to_fire = false;
+        self.root.tick(&mut self.blackboard)
+    }
+
+    /// Flee when badly damaged, back off to keep a distance from the ship while shooting at it,
+    /// circle it a bit further out, close in when it is nearby and patrol otherwise
+    pub fn drone() -> Self {
+        Self::new(BehaviorNode::Selector(vec![
+            BehaviorNode::Sequence(vec![
+                BehaviorNode::Condition(Condition::HealthBelow(0.35)),
+                BehaviorNode::Action(Action::Flee { speed: 1.2 }),
+            ]),
+            BehaviorNode::Sequence(vec![
+                BehaviorNode::Condition(Condition::ShipWithin(2.5)),
+                BehaviorNode::Action(Action::Attack),
+                BehaviorNode::Action(Action::Retreat {
+                    speed: 0.8,
+                    distance: 2.0,
+                }),
+            ]),
+            BehaviorNode::Sequence(vec![
+                BehaviorNode::Condition(Condition::ShipWithin(4.0)),
+                BehaviorNode::Action(Action::Attack),
+                BehaviorNode::Action(Action::Strafe { speed: 0.6 }),
+            ]),
+            BehaviorNode::Sequence(vec![
+                BehaviorNode::Condition(Condition::ShipWithin(7.0)),
+                BehaviorNode::Action(Action::Attack),
+                BehaviorNode::Action(Action::Approach { speed: 0.5 }),
+            ]),
+            BehaviorNode::Action(Action::Patrol),
+        ]))
+    }
+
+    /// Fire when the ship is in range, movement is left to the turret's drift
+    pub fn turret(range: f32) -> Self {
+        Self::new(BehaviorNode::Sequence(vec![
+            BehaviorNode::Condition(Condition::ShipWithin(range)),
+            BehaviorNode::Action(Action::Attack),
+        ]))
+    }
+
+    /// Only shoot, formation followers are moved by their slot
+    pub fn gunner() -> Self {
+        Self::new(BehaviorNode::Action(Action::Attack))
+    }
+}
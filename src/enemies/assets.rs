@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+
+/// Scene, mesh and material handles shared by every enemy of the same kind
+#[derive(Resource)]
+pub struct EnemyAssets {
+    pub drone_scene: Handle<Scene>,
+    pub kamikaze_mesh: Handle<Mesh>,
+    pub kamikaze_material: Handle<StandardMaterial>,
+    pub turret_base_mesh: Handle<Mesh>,
+    pub turret_barrel_mesh: Handle<Mesh>,
+    pub turret_material: Handle<StandardMaterial>,
+    pub boss_core_mesh: Handle<Mesh>,
+    pub boss_core_material: Handle<StandardMaterial>,
+    pub boss_plate_mesh: Handle<Mesh>,
+    pub boss_plate_material: Handle<StandardMaterial>,
+    pub boss_pod_mesh: Handle<Mesh>,
+    pub boss_weak_point_mesh: Handle<Mesh>,
+    pub boss_weak_point_material: Handle<StandardMaterial>,
+    pub asteroid_mesh: Handle<Mesh>,
+    pub asteroid_material: Handle<StandardMaterial>,
+}
+
+/// Loads the enemy models once, so respawning an enemy never reloads its model
+pub fn setup_enemy_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.insert_resource(EnemyAssets {
+        drone_scene: asset_server.load("models/enemies/drone.glb#Scene0"),
+        kamikaze_mesh: meshes.add(Cone {
+            radius: 0.1,
+            height: 0.3,
+        }),
+        kamikaze_material: materials.add(StandardMaterial {
+            base_color: Color::srgb(1.0, 0.4, 0.0), // Orange warning color
+            emissive: Color::srgb(0.6, 0.1, 0.0).into(),
+            ..default()
+        }),
+        turret_base_mesh: meshes.add(Sphere::new(0.15)),
+        turret_barrel_mesh: meshes.add(Cylinder::new(0.03, 0.3)),
+        turret_material: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.5, 0.5, 0.6), // Gunmetal grey
+            metallic: 0.8,
+            ..default()
+        }),
+        boss_core_mesh: meshes.add(Sphere::new(0.4)),
+        boss_core_material: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.4, 0.05, 0.1), // Dark crimson
+            metallic: 0.5,
+            ..default()
+        }),
+        boss_plate_mesh: meshes.add(Cuboid::new(0.08, 0.4, 0.3)),
+        boss_plate_material: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.3, 0.3, 0.35), // Dull armor grey
+            metallic: 0.9,
+            perceptual_roughness: 0.6,
+            ..default()
+        }),
+        boss_pod_mesh: meshes.add(Capsule3d::new(0.1, 0.2)),
+        boss_weak_point_mesh: meshes.add(Sphere::new(0.12)),
+        boss_weak_point_material: materials.add(StandardMaterial {
+            base_color: Color::srgb(1.0, 0.9, 0.2),
+            emissive: Color::srgb(1.0, 0.6, 0.0).into(), // Glowing so players know where to aim
+            ..default()
+        }),
+        asteroid_mesh: meshes.add(Sphere::new(1.0).mesh().uv(8, 6)), // Low poly for a rocky look
+        asteroid_material: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.45, 0.38, 0.32), // Brownish rock
+            perceptual_roughness: 0.95,
+            ..default()
+        }),
+    });
+}
@@ -0,0 +1,115 @@
+use crate::collision::{Collidable, Team};
+use crate::enemies::damage_feedback::DamageFeedback;
+use crate::enemies::{Enemy, EnemyAssets};
+use crate::pool::{EntityPool, PoolKind, acquire_or_spawn};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use rand::Rng;
+
+/// Size of the asteroids entering the screen, each split makes fragments one size smaller
+pub const ASTEROID_MAX_SIZE: u32 = 3;
+
+/// An enemy that breaks into smaller copies of itself when destroyed
+#[derive(Component, Clone)]
+pub struct Splitter {
+    pub size: u32,         // Fragments are one size smaller, size 1 does not split
+    pub fragments: u32,    // Number of fragments spawned on death
+    pub spread_speed: f32, // Speed added to the inherited velocity, away from the center
+}
+
+/// Radius of an asteroid of the given size
+pub fn asteroid_radius(size: u32) -> f32 {
+    0.1 * size as f32
+}
+
+pub fn spawn_asteroid(
+    commands: &mut Commands,
+    enemy_assets: &Res<EnemyAssets>,
+    pool: &mut ResMut<EntityPool>,
+    position: Vec3,
+    velocity: Vec3,
+    size: u32,
+) -> Entity {
+    let mut rng = rand::thread_rng();
+    let spin = Vec3::new(
+        rng.gen_range(-1.0..1.0),
+        rng.gen_range(-1.0..1.0),
+        rng.gen_range(-1.0..1.0),
+    );
+
+    let (asteroid_entity, fresh) = acquire_or_spawn(
+        commands,
+        pool,
+        PoolKind::Asteroid,
+        (
+            Enemy {
+                // Smaller fragments are worth less and are not replaced
+                score: 25 * size,
+                respawn: size == ASTEROID_MAX_SIZE,
+                size: 0.5 * size as f32,
+            },
+            DamageFeedback::default(),
+            Splitter {
+                size,
+                fragments: 2 + size / 2,
+                spread_speed: 0.4,
+            },
+            Collidable::new(15.0 * size as f32, 10.0 * size as f32, Team::Enemy),
+            Velocity {
+                linvel: velocity,
+                angvel: spin,
+            },
+            Transform {
+                translation: position,
+                rotation: Quat::IDENTITY,
+                scale: Vec3::splat(asteroid_radius(size)), // The collider scales with the mesh
+            },
+        ),
+    );
+
+    if fresh {
+        commands.entity(asteroid_entity).insert((
+            RigidBody::KinematicVelocityBased,
+            Collider::ball(1.0),
+            ActiveEvents::COLLISION_EVENTS,
+            ActiveCollisionTypes::KINEMATIC_KINEMATIC,
+            Mesh3d(enemy_assets.asteroid_mesh.clone()),
+            MeshMaterial3d(enemy_assets.asteroid_material.clone()),
+        ));
+    }
+
+    asteroid_entity
+}
+
+/// Break dying asteroids into smaller fragments flying apart from their velocity
+pub fn split_dying_enemies(
+    mut commands: Commands,
+    enemy_assets: Res<EnemyAssets>,
+    mut pool: ResMut<EntityPool>,
+    splitters: Query<(&Splitter, &Collidable, &Transform, &Velocity), With<Enemy>>,
+) {
+    let mut rng = rand::thread_rng();
+
+    for (splitter, collidable, transform, velocity) in splitters.iter() {
+        if collidable.is_alive() || splitter.size <= 1 {
+            continue;
+        }
+
+        let fragment_size = splitter.size - 1;
+        let start_angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        for i in 0..splitter.fragments {
+            // Spread the fragments evenly around the center
+            let angle = start_angle + std::f32::consts::TAU * i as f32 / splitter.fragments as f32;
+            let direction = Vec3::new(angle.cos(), angle.sin(), 0.0);
+
+            spawn_asteroid(
+                &mut commands,
+                &enemy_assets,
+                &mut pool,
+                transform.translation + direction * asteroid_radius(fragment_size),
+                velocity.linvel + direction * splitter.spread_speed,
+                fragment_size,
+            );
+        }
+    }
+}
@@ -0,0 +1,416 @@
+use crate::camera_shake::CameraShake;
+use crate::collision::{Collidable, Team};
+use crate::enemies::damage_feedback::DamageFeedback;
+use crate::enemies::{Enemy, EnemyAssets};
+use crate::hud::PlayerScore;
+use crate::nebula::NebulaSettings;
+use crate::play_area::PlayArea;
+use crate::pool::EntityPool;
+use crate::projectiles::guidance::intercept_point;
+use crate::projectiles::{ProjectileAssets, spawn_cannon_ball_projectile, spawn_flak_projectile};
+use crate::ship::Ship;
+use crate::spatial::{SpatialIndex, SpatialKind};
+use crate::starfield::Warp;
+use crate::weapons::weapon::{Weapon, fire_weapon_from};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// The attack phases of a boss, advanced by destroying its parts
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum BossPhase {
+    Assault, // Weapon pods fire aimed shots at the ship
+    Barrage, // Pods are gone, the core sweeps flak while its weak points hide behind armor
+    Exposed, // Weak points are gone, the core can be damaged and fires at the ship
+}
+
+impl BossPhase {
+    /// Every phase, in the order a boss goes through them
+    pub const ALL: [BossPhase; 3] = [BossPhase::Assault, BossPhase::Barrage, BossPhase::Exposed];
+}
+
+/// The role of a boss part, each part is a child entity with its own Collidable
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BossPartRole {
+    WeaponPod,  // Carries a weapon, destroying every pod ends the assault phase
+    ArmorPlate, // Sturdy shield in front of a weak point
+    WeakPoint,  // Fragile, destroying every weak point exposes the core
+}
+
+#[derive(Component)]
+pub struct BossPart {
+    pub role: BossPartRole,
+}
+
+/// A boss enemy, its root entity is the core
+#[derive(Component)]
+pub struct Boss {
+    pub phase: BossPhase,
+    /// Horizontal position where the boss stops entering the screen
+    pub hover_x: f32,
+    /// Time since the boss spawned, drives its movement and barrage sweep
+    pub age: f32,
+    /// Hit points of the core and every part still alive
+    pub hit_points: f32,
+    /// Hit points of the core and every part when the boss spawned
+    pub max_hit_points: f32,
+}
+
+/// Spawns a boss each time the player's score crosses the next threshold
+#[derive(Resource)]
+pub struct BossSpawner {
+    pub next_score: u32,
+    pub score_interval: u32,
+}
+
+impl Default for BossSpawner {
+    fn default() -> Self {
+        Self {
+            next_score: 5000,
+            score_interval: 10000,
+        }
+    }
+}
+
+/// Creates the core's flak gun, only fired once the weapon pods are destroyed
+pub fn create_boss_core_weapon() -> Weapon {
+    Weapon::new()
+        .with_fire_cooldown(0.2)
+        .with_burst(6, 1.5)
+        .with_projectile_spawner(spawn_flak_projectile)
+        .with_projectile_spawn_offset(Vec3::new(0.5, 0.0, 0.0)) // Spawn outside the core
+        .with_projectile_spawn_speed_vector(Vec3::new(2.5, 0.0, 0.0))
+}
+
+/// Creates a weapon pod's cannon
+pub fn create_boss_pod_weapon() -> Weapon {
+    Weapon::new()
+        .with_fire_cooldown(0.8)
+        .with_projectile_spawner(spawn_cannon_ball_projectile)
+        .with_projectile_spawn_offset(Vec3::new(0.25, 0.0, 0.0)) // Spawn outside the pod
+        .with_projectile_spawn_speed_vector(Vec3::new(4.0, 0.0, 0.0))
+}
+
+/// Spawns a boss part as a child of the boss, with its own collider so it receives its own hits
+fn spawn_boss_part(
+    commands: &mut Commands,
+    boss_entity: Entity,
+    role: BossPartRole,
+    collidable: Collidable,
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+    collider: Collider,
+    translation: Vec3,
+) -> Entity {
+    let part_entity = commands
+        .spawn((
+            BossPart { role },
+            collidable,
+            DamageFeedback::default(), // Parts flash on their own hits
+            Mesh3d(mesh),
+            MeshMaterial3d(material),
+            Transform::from_translation(translation),
+            collider,
+            ActiveEvents::COLLISION_EVENTS,
+            ActiveCollisionTypes::KINEMATIC_KINEMATIC,
+        ))
+        .id();
+    commands.entity(boss_entity).add_child(part_entity);
+    part_entity
+}
+
+pub fn spawn_boss(
+    commands: &mut Commands,
+    enemy_assets: &Res<EnemyAssets>,
+    position: Vec3,
+    hover_x: f32,
+) -> Entity {
+    let mut core_collidable = Collidable::new(50.0, 300.0, Team::Enemy);
+    core_collidable.invulnerable = true; // Until every weak point is destroyed
+
+    let pod_collidable = Collidable::new(20.0, 40.0, Team::Enemy);
+    let weak_point_collidable = Collidable::new(20.0, 30.0, Team::Enemy);
+    let plate_collidable = Collidable::new(20.0, 80.0, Team::Enemy);
+    // One pod, weak point and plate on each side of the core
+    let max_hit_points = core_collidable.max_hit_points
+        + 2.0
+            * (pod_collidable.max_hit_points
+                + weak_point_collidable.max_hit_points
+                + plate_collidable.max_hit_points);
+
+    let mut core_weapon = create_boss_core_weapon();
+    core_weapon.weapon_rotation = Quat::from_rotation_z(std::f32::consts::PI); // Face the ship
+
+    let boss_entity = commands
+        .spawn((
+            Enemy {
+                score: 5000,
+                respawn: false, // Bosses are not replaced, regular enemies keep coming meanwhile
+                size: 4.0,
+            },
+            Boss {
+                phase: BossPhase::Assault,
+                hover_x,
+                age: 0.0,
+                hit_points: max_hit_points,
+                max_hit_points,
+            },
+            core_collidable,
+            core_weapon,
+            DamageFeedback::default(),
+            Transform::from_translation(position),
+            Visibility::default(),
+            Velocity::default(),
+            RigidBody::KinematicVelocityBased,
+            Collider::ball(0.4),
+            ActiveEvents::COLLISION_EVENTS,
+            ActiveCollisionTypes::KINEMATIC_KINEMATIC,
+            Mesh3d(enemy_assets.boss_core_mesh.clone()),
+            MeshMaterial3d(enemy_assets.boss_core_material.clone()),
+        ))
+        .id();
+
+    for side in [1.0, -1.0] {
+        // Weapon pods above and below the core
+        let pod_entity = spawn_boss_part(
+            commands,
+            boss_entity,
+            BossPartRole::WeaponPod,
+            pod_collidable.clone(),
+            enemy_assets.boss_pod_mesh.clone(),
+            enemy_assets.turret_material.clone(),
+            Collider::capsule_y(0.1, 0.1),
+            Vec3::new(0.0, 0.85 * side, 0.0),
+        );
+        commands.entity(pod_entity).insert(create_boss_pod_weapon());
+
+        // Weak points on the front of the core, each shielded by an armor plate
+        spawn_boss_part(
+            commands,
+            boss_entity,
+            BossPartRole::WeakPoint,
+            weak_point_collidable.clone(),
+            enemy_assets.boss_weak_point_mesh.clone(),
+            enemy_assets.boss_weak_point_material.clone(),
+            Collider::ball(0.12),
+            Vec3::new(-0.25, 0.45 * side, 0.0),
+        );
+        spawn_boss_part(
+            commands,
+            boss_entity,
+            BossPartRole::ArmorPlate,
+            plate_collidable.clone(),
+            enemy_assets.boss_plate_mesh.clone(),
+            enemy_assets.boss_plate_material.clone(),
+            Collider::cuboid(0.04, 0.2, 0.15),
+            Vec3::new(-0.5, 0.45 * side, 0.0),
+        );
+    }
+
+    info!("A boss appeared");
+    boss_entity
+}
+
+/// Spawn a boss when the score reaches the next threshold, one boss at a time
+pub fn spawn_boss_at_score(
+    mut commands: Commands,
+    enemy_assets: Res<EnemyAssets>,
+    mut boss_spawner: ResMut<BossSpawner>,
+    player_score: Res<PlayerScore>,
+    bosses: Query<(), With<Boss>>,
+    play_area: Res<PlayArea>,
+) {
+    if player_score.score < boss_spawner.next_score || !bosses.is_empty() {
+        return;
+    }
+    boss_spawner.next_score = player_score.score + boss_spawner.score_interval;
+    // Fly in from off-screen and hover near the right edge
+    let position = Vec3::new(play_area.right() + 1.0, play_area.center.y, 0.0);
+    spawn_boss(
+        &mut commands,
+        &enemy_assets,
+        position,
+        play_area.right() - 1.5,
+    );
+}
+
+/// Jump to warp speed when a boss is defeated, moving on to the next stretch of space
+pub fn warp_after_boss_defeat(
+    bosses: Query<&Collidable, With<Boss>>,
+    mut warp: ResMut<Warp>,
+    mut nebula_settings: ResMut<NebulaSettings>,
+) {
+    let warp_duration = 3.0;
+
+    for collidable in bosses.iter() {
+        if !collidable.is_alive() {
+            warp.start(warp_duration);
+            nebula_settings.next_level(); // The warp hides the background change
+        }
+    }
+}
+
+/// Advance boss phases from the parts still alive, the core only takes damage once exposed.
+/// Also sums the hit points of the core and its parts for the HUD.
+pub fn update_boss_phases(
+    mut bosses: Query<(Entity, &mut Boss, &mut Collidable), Without<BossPart>>,
+    parts: Query<(&BossPart, &Collidable, &ChildOf)>,
+) {
+    for (boss_entity, mut boss, mut core) in bosses.iter_mut() {
+        let any_alive = |role: BossPartRole| {
+            parts.iter().any(|(part, collidable, child_of)| {
+                child_of.parent() == boss_entity && part.role == role && collidable.is_alive()
+            })
+        };
+
+        let phase = if any_alive(BossPartRole::WeaponPod) {
+            BossPhase::Assault
+        } else if any_alive(BossPartRole::WeakPoint) {
+            BossPhase::Barrage
+        } else {
+            BossPhase::Exposed
+        };
+
+        boss.hit_points = core.hit_points
+            + parts
+                .iter()
+                .filter(|(_, _, child_of)| child_of.parent() == boss_entity)
+                .map(|(_, collidable, _)| collidable.hit_points)
+                .sum::<f32>();
+
+        if phase != boss.phase {
+            debug!("Boss entered the {:?} phase", phase);
+            boss.phase = phase;
+        }
+        core.invulnerable = phase != BossPhase::Exposed;
+    }
+}
+
+/// Bosses enter from the right, then hover up and down, faster once exposed
+pub fn move_bosses(mut bosses: Query<(&mut Boss, &Transform, &mut Velocity)>, time: Res<Time>) {
+    for (mut boss, transform, mut velocity) in bosses.iter_mut() {
+        boss.age += time.delta_secs();
+
+        if transform.translation.x > boss.hover_x {
+            velocity.linvel = Vec3::new(-0.8, 0.0, 0.0);
+        } else {
+            let frequency = match boss.phase {
+                BossPhase::Exposed => 1.6,
+                _ => 0.8,
+            };
+            velocity.linvel = Vec3::new(0.0, (boss.age * frequency).cos() * 0.8, 0.0);
+        }
+    }
+}
+
+/// Fire the boss weapons allowed by its current phase
+pub fn boss_attack(
+    mut bosses: Query<(Entity, &Boss, &mut Weapon, &Transform, &Velocity), Without<BossPart>>,
+    mut pods: Query<(&BossPart, &ChildOf, &GlobalTransform, &mut Weapon), Without<Boss>>,
+    ships: Query<&Velocity, With<Ship>>,
+    spatial_index: Res<SpatialIndex>,
+    mut commands: Commands,
+    projectile_assets: Res<ProjectileAssets>,
+    mut pool: ResMut<EntityPool>,
+    asset_server: Res<AssetServer>,
+    mut scene_spawner: ResMut<SceneSpawner>,
+    mut camera_shake: ResMut<CameraShake>,
+) {
+    let attack_range = 12.0;
+    let recoil_trauma = 0.05; // Each boss shot rattles the screen a little
+
+    for (boss_entity, boss, mut core_weapon, transform, velocity) in bosses.iter_mut() {
+        let ship =
+            spatial_index.nearest_in_radius(transform.translation, attack_range, SpatialKind::Ship);
+        let ship_velocity = ship
+            .and_then(|ship| ships.get(ship.entity).ok())
+            .map_or(Vec3::ZERO, |velocity| velocity.linvel);
+
+        match boss.phase {
+            BossPhase::Assault => {
+                let Some(ship) = ship else {
+                    continue;
+                };
+                for (part, child_of, part_transform, mut pod_weapon) in pods.iter_mut() {
+                    if child_of.parent() != boss_entity || part.role != BossPartRole::WeaponPod {
+                        continue;
+                    }
+
+                    // Pods lead the ship, aiming where it will be when the shot arrives
+                    let pod_transform = part_transform.compute_transform();
+                    let aim_point = intercept_point(
+                        pod_transform.translation,
+                        pod_weapon.projectile_spawn_speed_vector.length(),
+                        ship.position,
+                        ship_velocity,
+                    );
+                    let Some(direction) = (aim_point - pod_transform.translation).try_normalize()
+                    else {
+                        continue;
+                    };
+                    pod_weapon.weapon_rotation = pod_transform.rotation.inverse()
+                        * Quat::from_rotation_arc(Vec3::X, direction);
+
+                    if pod_weapon.can_fire() {
+                        camera_shake.add_trauma(recoil_trauma);
+                    }
+                    fire_weapon_from(
+                        &mut pod_weapon,
+                        &pod_transform,
+                        velocity.linvel,
+                        Team::Enemy,
+                        &mut commands,
+                        &projectile_assets,
+                        &mut pool,
+                        &asset_server,
+                        &mut scene_spawner,
+                    );
+                }
+            }
+            BossPhase::Barrage => {
+                // Sweep the flak gun up and down in front of the boss
+                let sweep = (boss.age * 1.5).sin() * std::f32::consts::FRAC_PI_3;
+                core_weapon.weapon_rotation = Quat::from_rotation_z(std::f32::consts::PI + sweep);
+                if core_weapon.can_fire() {
+                    camera_shake.add_trauma(recoil_trauma);
+                }
+                fire_weapon_from(
+                    &mut core_weapon,
+                    transform,
+                    velocity.linvel,
+                    Team::Enemy,
+                    &mut commands,
+                    &projectile_assets,
+                    &mut pool,
+                    &asset_server,
+                    &mut scene_spawner,
+                );
+            }
+            BossPhase::Exposed => {
+                // The exposed core fires its flak straight at the ship
+                let Some(ship) = ship else {
+                    continue;
+                };
+                let Some(direction) = (ship.position - transform.translation).try_normalize()
+                else {
+                    continue;
+                };
+                core_weapon.weapon_rotation =
+                    transform.rotation.inverse() * Quat::from_rotation_arc(Vec3::X, direction);
+                if core_weapon.can_fire() {
+                    camera_shake.add_trauma(recoil_trauma);
+                }
+                fire_weapon_from(
+                    &mut core_weapon,
+                    transform,
+                    velocity.linvel,
+                    Team::Enemy,
+                    &mut commands,
+                    &projectile_assets,
+                    &mut pool,
+                    &asset_server,
+                    &mut scene_spawner,
+                );
+            }
+        }
+    }
+}
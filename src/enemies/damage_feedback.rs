@@ -0,0 +1,131 @@
+use crate::collision::Collidable;
+use bevy::color::Mix;
+use bevy::prelude::*;
+
+/// How long an enemy flashes white after being hit, in seconds
+pub const HIT_FLASH_DURATION: f32 = 0.08;
+
+/// Flashes an entity's meshes white when it is hit and tints them toward red as it loses hit points.
+/// Covers every mesh below the entity, including glTF scene children, down to the next entity with
+/// its own DamageFeedback (boss parts react to their own hits).
+#[derive(Component, Clone, Default)]
+pub struct DamageFeedback {
+    /// Hit points seen last frame, to detect hits
+    pub last_hit_points: Option<f32>,
+    pub flash_timer: f32,
+    /// Health ratio and flash state last written to the materials
+    pub applied: Option<(f32, bool)>,
+}
+
+/// A mesh whose material was cloned for its entity alone, remembering the shared material's colors
+#[derive(Component, Clone)]
+pub struct InstanceMaterial {
+    pub base_color: Color,
+    pub emissive: LinearRgba,
+}
+
+/// Walk up from a mesh entity to the nearest entity with a DamageFeedback
+fn find_feedback_owner(
+    entity: Entity,
+    feedback_owners: &Query<(), With<DamageFeedback>>,
+    parents: &Query<&ChildOf>,
+) -> Option<Entity> {
+    let mut current = entity;
+    loop {
+        if feedback_owners.contains(current) {
+            return Some(current);
+        }
+        current = parents.get(current).ok()?.parent();
+    }
+}
+
+pub fn update_damage_feedback(
+    mut commands: Commands,
+    mut owners: Query<(Entity, &mut DamageFeedback, Ref<Collidable>)>,
+    feedback_owners: Query<(), With<DamageFeedback>>,
+    new_meshes: Query<Entity, Added<MeshMaterial3d<StandardMaterial>>>,
+    children: Query<&Children>,
+    parents: Query<&ChildOf>,
+    mesh_materials: Query<(&MeshMaterial3d<StandardMaterial>, Option<&InstanceMaterial>)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    time: Res<Time>,
+) {
+    // Scene children are spawned after the enemy, new meshes send their owner looking for them
+    for mesh_entity in new_meshes.iter() {
+        let owner = find_feedback_owner(mesh_entity, &feedback_owners, &parents);
+        if let Some(Ok((_, mut feedback, _))) = owner.map(|owner| owners.get_mut(owner)) {
+            feedback.applied = None;
+        }
+    }
+
+    for (owner_entity, mut feedback, collidable) in owners.iter_mut() {
+        if collidable.is_changed() {
+            // A drop in hit points since last frame is a hit
+            if feedback
+                .last_hit_points
+                .is_some_and(|last_hit_points| collidable.hit_points < last_hit_points)
+            {
+                feedback.flash_timer = HIT_FLASH_DURATION;
+            }
+            if feedback.last_hit_points != Some(collidable.hit_points) {
+                feedback.last_hit_points = Some(collidable.hit_points);
+            }
+        }
+        if feedback.flash_timer > 0.0 {
+            feedback.flash_timer = (feedback.flash_timer - time.delta_secs()).max(0.0);
+        }
+
+        let health_ratio = (collidable.hit_points / collidable.max_hit_points).clamp(0.0, 1.0);
+        let flashing = feedback.flash_timer > 0.0;
+        let state = (health_ratio, flashing);
+        // Only walk the hierarchy after a hit, when the flash ends or when there are new meshes
+        if feedback.applied == Some(state) {
+            continue;
+        }
+        let mut incomplete = false;
+
+        let mesh_entities = std::iter::once(owner_entity)
+            .chain(children.iter_descendants(owner_entity))
+            .filter(|entity| {
+                find_feedback_owner(*entity, &feedback_owners, &parents) == Some(owner_entity)
+            });
+
+        for mesh_entity in mesh_entities {
+            let Ok((mesh_material, instance_material)) = mesh_materials.get(mesh_entity) else {
+                continue;
+            };
+
+            let Some(instance_material) = instance_material else {
+                // Give the mesh its own copy of the material, so tinting it leaves other enemies alone
+                if let Some(material) = materials.get(&mesh_material.0).cloned() {
+                    commands.entity(mesh_entity).insert((
+                        InstanceMaterial {
+                            base_color: material.base_color,
+                            emissive: material.emissive,
+                        },
+                        MeshMaterial3d(materials.add(material)),
+                    ));
+                }
+                incomplete = true; // Try again once the material is loaded and copied
+                continue;
+            };
+
+            if let Some(material) = materials.get_mut(&mesh_material.0) {
+                if flashing {
+                    material.base_color = Color::WHITE;
+                    material.emissive = LinearRgba::WHITE;
+                } else {
+                    // Original colors at full health, mostly red when nearly dead
+                    let tint = (1.0 - health_ratio) * 0.8;
+                    material.base_color = instance_material
+                        .base_color
+                        .mix(&Color::srgb(1.0, 0.0, 0.0), tint);
+                    material.emissive = instance_material.emissive;
+                }
+            }
+        }
+
+        // New copies only get the current state once their entity has the InstanceMaterial
+        feedback.applied = if incomplete { None } else { Some(state) };
+    }
+}
@@ -1,82 +1,92 @@
 use crate::collision::{Collidable, Team};
+use crate::enemies::ai::BehaviorTree;
+use crate::enemies::damage_feedback::DamageFeedback;
+use crate::enemies::movement::{MovementPattern, PatternKind};
+use crate::enemies::{AimAtShip, EnemyAssets};
+use crate::fallback::{FallbackShape, SceneFallback};
+use crate::pool::{EntityPool, PoolKind, acquire_or_spawn};
 use crate::weapons::cannon::create_cannon;
-use crate::weapons::weapon::{Weapon, attach_weapon, fire_weapon};
+use crate::weapons::weapon::attach_weapon;
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
-pub fn drone_behave(
-    entity: Entity,
-    weapon: &mut Weapon,
-    transforms: &Query<&Transform>,
-    velocities: &Query<&Velocity>,
-    collidables: &Query<&Collidable>,
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &mut ResMut<Assets<StandardMaterial>>,
-    asset_server: &Res<AssetServer>,
-    scene_spawner: &mut ResMut<SceneSpawner>,
-) {
-    fire_weapon(
-        weapon,
-        entity,
-        transforms,
-        velocities,
-        collidables,
-        commands,
-        meshes,
-        materials,
-        asset_server,
-        scene_spawner,
-    );
-}
-
 pub fn spawn_drone(
     commands: &mut Commands,
+    enemy_assets: &Res<EnemyAssets>,
+    pool: &mut ResMut<EntityPool>,
     asset_server: &Res<AssetServer>,
     scene_spawner: &mut ResMut<SceneSpawner>,
     position: Vec3,
-) {
-    // Load the drone model
-    let drone_handle = asset_server.load("models/enemies/drone.glb#Scene0");
-
+    pattern: PatternKind,
+) -> Entity {
     // Enemies move slowly to the left (negative X direction)
     let left_velocity = Vec3::new(-0.2, 0.0, 0.0);
 
-    let drone_entity = commands
-        .spawn((
+    // Cannon attached to the drone, rotated 90 degrees around Y axis
+    let cannon_rotation = Quat::from_rotation_y(-std::f32::consts::FRAC_PI_2);
+    // Rotate 90 degrees left to face movement direction
+    let drone_rotation = Quat::from_rotation_y(-std::f32::consts::PI / 2.0);
+
+    let (drone_entity, fresh) = acquire_or_spawn(
+        commands,
+        pool,
+        PoolKind::Drone,
+        (
             super::Enemy {
                 score: 100,
-                behave: Some(drone_behave),
+                respawn: true,
+                size: 1.0,
             },
+            BehaviorTree::drone().with_model_rotation(drone_rotation),
+            DamageFeedback::default(),
             Collidable::new(20.0, super::ENEMY_HIT_POINTS, Team::Enemy), // 20 damage, 20 HP, enemy team
+            // Drones turn their cannon up to 30 degrees toward a nearby ship
+            AimAtShip {
+                base_rotation: cannon_rotation,
+                cone_half_angle: std::f32::consts::FRAC_PI_6,
+                range: 6.0,
+            },
             Velocity::linear(left_velocity),
-            RigidBody::KinematicVelocityBased,
-            ActiveEvents::COLLISION_EVENTS,
-            ActiveCollisionTypes::KINEMATIC_KINEMATIC,
+            // The pattern weaves around the drift to the left
+            MovementPattern::new(pattern, left_velocity),
             Transform {
                 translation: position,
-                rotation: Quat::from_rotation_y(-std::f32::consts::PI / 2.0), // Rotate 90 degrees left to face movement direction
+                rotation: drone_rotation,
                 scale: Vec3::splat(0.1),
             },
-            SceneRoot(drone_handle),
+        ),
+    );
+
+    let mut cannon = create_cannon(Vec3::ZERO);
+    cannon.fire_cooldown_duration *= 50.0; // Drones fire 10x slower than the default cannon
+    cannon.projectile_spawn_speed_vector *= 0.1; // Projectiles are 10x slower
+
+    if fresh {
+        commands.entity(drone_entity).insert((
+            RigidBody::KinematicVelocityBased,
+            ActiveEvents::COLLISION_EVENTS,
+            ActiveCollisionTypes::KINEMATIC_KINEMATIC,
+            SceneRoot(enemy_assets.drone_scene.clone()),
+            SceneFallback(FallbackShape::Drone),
             AsyncSceneCollider {
                 shape: Some(ComputedColliderShape::ConvexHull),
                 named_shapes: Default::default(),
             },
-        ))
-        .id();
+        ));
+        attach_weapon(
+            commands,
+            asset_server,
+            scene_spawner,
+            drone_entity,
+            cannon,
+            cannon_rotation,
+            Vec3::splat(1.0),
+        );
+    } else {
+        // Recycled drones still carry their cannon mesh, only the weapon needs resetting
+        cannon.weapon_rotation = cannon_rotation;
+        commands.entity(drone_entity).insert(cannon);
+    }
 
-    // Attach cannon to the drone, rotated 90 degrees around Y axis
-    let mut cannon = create_cannon(Vec3::ZERO);
-    cannon.fire_cooldown_duration *= 50.0; // Drones fire 10x slower than the default cannon
-    cannon.projectile_spawn_speed_vector *= 0.1; // Projectiles are 10x slower
-    attach_weapon(
-        commands,
-        asset_server,
-        scene_spawner,
-        drone_entity,
-        cannon,
-        Quat::from_rotation_y(-std::f32::consts::FRAC_PI_2),
-        Vec3::splat(1.0),
-    );
+    drone_entity
 }
@@ -0,0 +1,189 @@
+use crate::collision::Collidable;
+use crate::enemies::ai::BehaviorTree;
+use crate::enemies::drones::spawn_drone;
+use crate::enemies::kamikaze::Kamikaze;
+use crate::enemies::movement::{MovementPattern, PatternKind};
+use crate::enemies::{Enemy, EnemyAssets};
+use crate::pool::EntityPool;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use rand::Rng;
+
+/// The shape followers take around their leader
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FormationShape {
+    V,    // Two trailing diagonals
+    Line, // Single file behind the leader
+    Ring, // Evenly spread around the leader
+}
+
+impl FormationShape {
+    /// Offset from the leader of the slot at the given index, out of `count` slots.
+    /// Formations fly to the left, so followers trail on the right.
+    pub fn slot_offset(&self, index: usize, count: usize, spacing: f32) -> Vec3 {
+        match self {
+            FormationShape::V => {
+                let row = (index / 2 + 1) as f32;
+                let side = if index.is_multiple_of(2) { 1.0 } else { -1.0 };
+                Vec3::new(row * spacing, side * row * spacing * 0.7, 0.0)
+            }
+            FormationShape::Line => Vec3::new((index + 1) as f32 * spacing, 0.0, 0.0),
+            FormationShape::Ring => {
+                let angle = std::f32::consts::TAU * index as f32 / count.max(1) as f32;
+                Vec3::new(angle.cos(), angle.sin(), 0.0) * spacing
+            }
+        }
+    }
+}
+
+/// A formation led by the entity carrying this component
+#[derive(Component, Clone)]
+pub struct Formation {
+    pub shape: FormationShape,
+    pub spacing: f32,
+    /// Followers in slot order, dead followers are removed so the others close the gaps
+    pub members: Vec<Entity>,
+}
+
+/// A follower holding a slot in its leader's formation
+#[derive(Component, Clone, Copy)]
+pub struct FormationSlot {
+    pub leader: Entity,
+}
+
+/// Spawn a drone leading a formation of drone followers, returns the leader
+pub fn spawn_formation(
+    commands: &mut Commands,
+    enemy_assets: &Res<EnemyAssets>,
+    pool: &mut ResMut<EntityPool>,
+    asset_server: &Res<AssetServer>,
+    scene_spawner: &mut ResMut<SceneSpawner>,
+    position: Vec3,
+    shape: FormationShape,
+    follower_count: usize,
+) -> Entity {
+    let spacing = 0.4;
+
+    // The leader flies a gentle wave, followers copy it from their slots
+    let leader_entity = spawn_drone(
+        commands,
+        enemy_assets,
+        pool,
+        asset_server,
+        scene_spawner,
+        position,
+        PatternKind::Sine {
+            amplitude: 0.6,
+            frequency: 0.2,
+        },
+    );
+
+    let mut members = Vec::with_capacity(follower_count);
+    for index in 0..follower_count {
+        let follower_entity = spawn_drone(
+            commands,
+            enemy_assets,
+            pool,
+            asset_server,
+            scene_spawner,
+            position + shape.slot_offset(index, follower_count, spacing),
+            PatternKind::Linear,
+        );
+        commands
+            .entity(follower_entity)
+            .remove::<MovementPattern>() // Followers are driven by their slot
+            .insert((
+                FormationSlot {
+                    leader: leader_entity,
+                },
+                Enemy {
+                    score: 100,
+                    respawn: false, // Only the leader is replaced, or formations would multiply
+                    size: 1.0,
+                },
+                BehaviorTree::gunner(),
+            ));
+        members.push(follower_entity);
+    }
+
+    commands.entity(leader_entity).insert(Formation {
+        shape,
+        spacing,
+        members,
+    });
+
+    leader_entity
+}
+
+/// Pick a random formation shape
+pub fn random_formation_shape() -> FormationShape {
+    let mut rng = rand::thread_rng();
+    match rng.gen_range(0..3) {
+        0 => FormationShape::V,
+        1 => FormationShape::Line,
+        _ => FormationShape::Ring,
+    }
+}
+
+/// Drop dead or recycled followers from their formation, so the remaining ones re-form
+pub fn reform_formations(
+    mut leaders: Query<(Entity, &mut Formation)>,
+    followers: Query<(&FormationSlot, &Collidable), With<Enemy>>,
+) {
+    for (leader_entity, mut formation) in leaders.iter_mut() {
+        formation.members.retain(|member| {
+            followers.get(*member).is_ok_and(|(slot, collidable)| {
+                slot.leader == leader_entity && collidable.is_alive()
+            })
+        });
+    }
+}
+
+/// Steer followers toward their slot, or break formation and dive at the ship once the leader is gone
+pub fn hold_formation_slots(
+    mut commands: Commands,
+    leaders: Query<(&Formation, &Transform, &Velocity, &Collidable), Without<FormationSlot>>,
+    mut followers: Query<
+        (Entity, &FormationSlot, &Transform, &mut Velocity),
+        (With<Enemy>, Without<Formation>),
+    >,
+) {
+    let catch_up_rate = 3.0; // Fraction of the distance to the slot closed per second
+    let max_correction = 1.5; // Maximum speed added to the leader's velocity to reach the slot
+
+    for (follower_entity, slot, transform, mut velocity) in followers.iter_mut() {
+        let leader = leaders
+            .get(slot.leader)
+            .ok()
+            .filter(|(_, _, _, collidable)| collidable.is_alive());
+
+        let Some((formation, leader_transform, leader_velocity, _)) = leader else {
+            // The leader was destroyed, followers break formation and attack,
+            // turning from the drone model's resting rotation rather than the kamikaze cone's
+            commands
+                .entity(follower_entity)
+                .remove::<FormationSlot>()
+                .insert(Kamikaze {
+                    model_rotation: transform.rotation,
+                    ..default()
+                });
+            continue;
+        };
+
+        let Some(index) = formation
+            .members
+            .iter()
+            .position(|member| *member == follower_entity)
+        else {
+            continue;
+        };
+
+        let slot_position = leader_transform.translation
+            + formation
+                .shape
+                .slot_offset(index, formation.members.len(), formation.spacing);
+        let correction = ((slot_position - transform.translation) * catch_up_rate)
+            .clamp_length_max(max_correction);
+        velocity.linvel = leader_velocity.linvel + correction;
+    }
+}
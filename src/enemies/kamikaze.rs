@@ -0,0 +1,284 @@
+use crate::camera_shake::CameraShake;
+use crate::collision::{Collidable, Team, apply_area_damage, find_collidable_root};
+use crate::enemies::damage_feedback::DamageFeedback;
+use crate::enemies::{Enemy, EnemyAssets};
+use crate::pool::{EntityPool, PoolKind, acquire_or_spawn};
+use crate::projectiles::guidance::rotate_toward;
+use crate::ship::Ship;
+use crate::spatial::{SpatialIndex, SpatialKind};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// An enemy that accelerates toward the ship and blows itself up on contact
+#[derive(Component, Clone)]
+pub struct Kamikaze {
+    pub acceleration: f32,
+    pub max_speed: f32,
+    pub turn_rate: f32, // Radians per second
+    pub detonation_radius: f32,
+    pub explosion_radius: f32,
+    pub explosion_damage: f32,
+    /// Rotation of the model when flying toward -X, the way enemies enter the screen
+    pub model_rotation: Quat,
+}
+
+impl Default for Kamikaze {
+    fn default() -> Self {
+        Self {
+            acceleration: 1.5,
+            max_speed: 3.0,
+            turn_rate: 1.2,
+            detonation_radius: 0.3,
+            explosion_radius: 0.8,
+            explosion_damage: 30.0,
+            model_rotation: Quat::from_rotation_arc(Vec3::Y, Vec3::NEG_X), // Cone tip first
+        }
+    }
+}
+
+pub fn spawn_kamikaze(
+    commands: &mut Commands,
+    enemy_assets: &Res<EnemyAssets>,
+    pool: &mut ResMut<EntityPool>,
+    position: Vec3,
+) -> Entity {
+    // Kamikazes enter the screen slowly before locking on the ship
+    let initial_velocity = Vec3::new(-0.5, 0.0, 0.0);
+
+    let kamikaze = Kamikaze::default();
+    let model_rotation = kamikaze.model_rotation;
+    let (kamikaze_entity, fresh) = acquire_or_spawn(
+        commands,
+        pool,
+        PoolKind::Kamikaze,
+        (
+            Enemy {
+                score: 150,
+                respawn: true,
+                size: 0.7,
+            },
+            kamikaze,
+            DamageFeedback::default(),
+            Collidable::new(60.0, 10.0, Team::Enemy), // Heavy collision damage, but fragile
+            Velocity::linear(initial_velocity),
+            Transform {
+                translation: position,
+                rotation: model_rotation,
+                scale: Vec3::ONE,
+            },
+        ),
+    );
+
+    if fresh {
+        commands.entity(kamikaze_entity).insert((
+            RigidBody::KinematicVelocityBased,
+            Collider::cone(0.15, 0.1),
+            ActiveEvents::COLLISION_EVENTS,
+            ActiveCollisionTypes::KINEMATIC_KINEMATIC,
+            Mesh3d(enemy_assets.kamikaze_mesh.clone()),
+            MeshMaterial3d(enemy_assets.kamikaze_material.clone()),
+        ));
+    }
+
+    kamikaze_entity
+}
+
+/// Accelerate kamikazes toward the ship, their turn rate limits how sharply they can dive
+pub fn steer_kamikazes(
+    mut kamikazes: Query<(&Kamikaze, &mut Velocity, &mut Transform), With<Enemy>>,
+    spatial_index: Res<SpatialIndex>,
+    time: Res<Time>,
+) {
+    let lock_on_range = 20.0;
+
+    for (kamikaze, mut velocity, mut transform) in kamikazes.iter_mut() {
+        let Some(ship) = spatial_index.nearest_in_radius(
+            transform.translation,
+            lock_on_range,
+            SpatialKind::Ship,
+        ) else {
+            continue;
+        };
+
+        let heading = transform.rotation * kamikaze.model_rotation.inverse() * Vec3::NEG_X;
+        let current_direction = velocity.linvel.normalize_or(heading);
+        let direction = rotate_toward(
+            current_direction,
+            ship.position - transform.translation,
+            kamikaze.turn_rate * time.delta_secs(),
+        );
+        let speed = (velocity.linvel.length() + kamikaze.acceleration * time.delta_secs())
+            .min(kamikaze.max_speed);
+
+        velocity.linvel = direction * speed;
+        // Turn the model from its resting heading, whatever way its mesh faces
+        transform.rotation =
+            Quat::from_rotation_arc(Vec3::NEG_X, direction) * kamikaze.model_rotation;
+    }
+}
+
+/// Blow up kamikazes that rammed the ship or came close enough to it,
+/// damaging everything of the player's team around them
+pub fn detonate_kamikazes(
+    mut kamikazes: Query<(Entity, &Kamikaze, &GlobalTransform, &mut Enemy)>,
+    mut targets: Query<(Entity, &GlobalTransform, &mut Collidable)>,
+    mut collision_events: MessageReader<CollisionEvent>,
+    parents: Query<&ChildOf>,
+    ships: Query<(), With<Ship>>,
+    spatial_index: Res<SpatialIndex>,
+    mut camera_shake: ResMut<CameraShake>,
+) {
+    // Kamikazes and the targets they rammed, which already took the contact damage
+    let mut rammed = Vec::new();
+    for collision_event in collision_events.read() {
+        if let CollisionEvent::Started(e1, e2, _) = collision_event {
+            let (Some(root_a), Some(root_b)) = (
+                find_collidable_root(*e1, &targets, &parents),
+                find_collidable_root(*e2, &targets, &parents),
+            ) else {
+                continue;
+            };
+            if kamikazes.contains(root_a) {
+                rammed.push((root_a, root_b));
+            }
+            if kamikazes.contains(root_b) {
+                rammed.push((root_b, root_a));
+            }
+        }
+    }
+
+    for (entity, kamikaze, transform, mut enemy) in kamikazes.iter_mut() {
+        let position = transform.translation();
+        // Contact with the ship hull can happen further than the detonation radius from its center
+        let rammed_ship = rammed
+            .iter()
+            .any(|(kamikaze, target)| *kamikaze == entity && ships.contains(*target));
+        let near_ship = spatial_index
+            .nearest_in_radius(position, kamikaze.detonation_radius, SpatialKind::Ship)
+            .is_some();
+        if !rammed_ship && !near_ship {
+            continue;
+        }
+
+        // The blast spares what the kamikaze rammed, so the ship is not hit twice
+        let mut excluded = vec![entity];
+        excluded.extend(
+            rammed
+                .iter()
+                .filter(|(kamikaze, _)| *kamikaze == entity)
+                .map(|(_, target)| *target),
+        );
+        apply_area_damage(
+            position,
+            kamikaze.explosion_radius,
+            kamikaze.explosion_damage,
+            Team::Enemy,
+            &excluded,
+            &mut targets,
+        );
+
+        camera_shake.add_trauma(0.4);
+
+        // Self-destruction does not reward the player
+        enemy.score = 0;
+        if let Ok((_, _, mut collidable)) = targets.get_mut(entity) {
+            collidable.hit_points = 0.0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy_rapier3d::rapier::geometry::CollisionEventFlags;
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.init_resource::<SpatialIndex>();
+        world.init_resource::<CameraShake>();
+        world.init_resource::<Messages<CollisionEvent>>();
+        world
+    }
+
+    fn spawn_kamikaze_at(world: &mut World, position: Vec3) -> Entity {
+        world
+            .spawn((
+                Kamikaze::default(),
+                Enemy {
+                    score: 150,
+                    respawn: true,
+                    size: 0.7,
+                },
+                Collidable::new(60.0, 10.0, Team::Enemy),
+                GlobalTransform::from_translation(position),
+            ))
+            .id()
+    }
+
+    fn spawn_player_target(world: &mut World, position: Vec3, ship: bool) -> Entity {
+        let mut target = world.spawn((
+            Collidable::new(10.0, 100.0, Team::Player),
+            GlobalTransform::from_translation(position),
+        ));
+        if ship {
+            target.insert(Ship);
+        }
+        target.id()
+    }
+
+    #[test]
+    fn ramming_the_ship_detonates_outside_the_detonation_radius() {
+        let mut world = world();
+        let ship = spawn_player_target(&mut world, Vec3::ZERO, true);
+        // Further from the ship's center than the detonation radius, as with a large hull
+        let kamikaze = spawn_kamikaze_at(&mut world, Vec3::new(0.6, 0.0, 0.0));
+        let bystander = spawn_player_target(&mut world, Vec3::new(1.0, 0.0, 0.0), false);
+        world
+            .resource_mut::<Messages<CollisionEvent>>()
+            .write(CollisionEvent::Started(
+                kamikaze,
+                ship,
+                CollisionEventFlags::empty(),
+            ));
+
+        world.run_system_once(detonate_kamikazes).unwrap();
+
+        let kamikaze_entity = world.entity(kamikaze);
+        assert_eq!(kamikaze_entity.get::<Collidable>().unwrap().hit_points, 0.0);
+        assert_eq!(kamikaze_entity.get::<Enemy>().unwrap().score, 0);
+        // The rammed ship only takes the contact damage, the bystander takes the blast
+        assert_eq!(world.get::<Collidable>(ship).unwrap().hit_points, 100.0);
+        assert!(world.get::<Collidable>(bystander).unwrap().hit_points < 100.0);
+    }
+
+    #[test]
+    fn nearing_the_ship_detonates_without_contact() {
+        let mut world = world();
+        let ship = spawn_player_target(&mut world, Vec3::ZERO, true);
+        world
+            .resource_mut::<SpatialIndex>()
+            .insert(ship, Vec3::ZERO, SpatialKind::Ship);
+        let kamikaze = spawn_kamikaze_at(&mut world, Vec3::new(0.2, 0.0, 0.0));
+
+        world.run_system_once(detonate_kamikazes).unwrap();
+
+        assert_eq!(world.get::<Enemy>(kamikaze).unwrap().score, 0);
+        assert!(world.get::<Collidable>(ship).unwrap().hit_points < 100.0);
+    }
+
+    #[test]
+    fn distant_kamikazes_do_not_detonate() {
+        let mut world = world();
+        let ship = spawn_player_target(&mut world, Vec3::ZERO, true);
+        world
+            .resource_mut::<SpatialIndex>()
+            .insert(ship, Vec3::ZERO, SpatialKind::Ship);
+        let kamikaze = spawn_kamikaze_at(&mut world, Vec3::new(2.0, 0.0, 0.0));
+
+        world.run_system_once(detonate_kamikazes).unwrap();
+
+        assert_eq!(world.get::<Enemy>(kamikaze).unwrap().score, 150);
+        assert_eq!(world.get::<Collidable>(ship).unwrap().hit_points, 100.0);
+    }
+}
@@ -1,184 +1,415 @@
+pub mod ai;
+pub mod assets;
+pub mod asteroid;
+pub mod boss;
+pub mod damage_feedback;
 pub mod drones;
+pub mod formation;
+pub mod kamikaze;
+pub mod movement;
+pub mod turret;
 
-use crate::collision::Collidable;
-use crate::hud::PlayerScore;
-use crate::projectiles::Projectile;
-use crate::weapons::weapon::Weapon;
+use crate::camera_shake::CameraShake;
+use crate::collision::{Collidable, despawn_dead_collidable};
+use crate::hud::{Combo, PlayerScore};
+use crate::particles::{ParticleBurst, ParticleEmitter};
+use crate::play_area::{PlayArea, update_play_area};
+use crate::pool::{EntityPool, Pooled, despawn_or_release};
+use crate::projectiles::{Projectile, ProjectileAssets};
+use crate::spatial::{SpatialIndex, SpatialKind};
+use crate::weapons::weapon::{Weapon, fire_weapon_from};
+use ai::BehaviorTree;
+pub use assets::EnemyAssets;
+use assets::setup_enemy_assets;
+use asteroid::{ASTEROID_MAX_SIZE, spawn_asteroid, split_dying_enemies};
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
+use boss::{
+    BossSpawner, boss_attack, move_bosses, spawn_boss_at_score, update_boss_phases,
+    warp_after_boss_defeat,
+};
+use damage_feedback::update_damage_feedback;
 use drones::spawn_drone;
+use formation::{hold_formation_slots, random_formation_shape, reform_formations, spawn_formation};
+use kamikaze::{detonate_kamikazes, spawn_kamikaze, steer_kamikazes};
+use movement::{PatternKind, follow_movement_patterns};
+use turret::{spawn_turret, track_turrets};
 
 pub const ENEMY_HIT_POINTS: f32 = 20.0;
 
-pub type EnemyBehave = fn(
-    Entity,
-    &mut Weapon,
-    &Query<&Transform>,
-    &Query<&Velocity>,
-    &Query<&Collidable>,
-    &mut Commands,
-    &mut ResMut<Assets<Mesh>>,
-    &mut ResMut<Assets<StandardMaterial>>,
-    &Res<AssetServer>,
-    &mut ResMut<SceneSpawner>,
-);
-
 #[derive(Component)]
 pub struct Enemy {
     pub score: u32,
-    pub behave: Option<EnemyBehave>,
+    pub respawn: bool, // Whether a new random enemy replaces this one when it dies or leaves
+    /// Rough size relative to a drone, scales the explosion when the enemy is destroyed
+    pub size: f32,
 }
 
 impl Default for Enemy {
     fn default() -> Self {
         Self {
             score: 100, // Default score for destroying an enemy
-            behave: None,
+            respawn: true,
+            size: 1.0,
         }
     }
 }
 
+/// Sent when an enemy is destroyed and scores
+#[derive(Message, Clone, Copy, Debug)]
+pub struct EnemyDestroyed {
+    pub position: Vec3,
+    /// Score awarded, multiplier included
+    pub score: u32,
+    pub multiplier: u32,
+}
+
+/// Makes an enemy turn its weapon toward the ship when the ship is in front of it
+#[derive(Component, Clone, Copy)]
+pub struct AimAtShip {
+    /// Weapon rotation when the ship is out of sight
+    pub base_rotation: Quat,
+    /// Maximum angle between the weapon's resting direction and the ship
+    pub cone_half_angle: f32,
+    /// Maximum distance to the ship
+    pub range: f32,
+}
+
 pub fn setup_enemies(
     mut commands: Commands,
+    enemy_assets: Res<EnemyAssets>,
+    mut pool: ResMut<EntityPool>,
     asset_server: Res<AssetServer>,
     mut scene_spawner: ResMut<SceneSpawner>,
+    play_area: Res<PlayArea>,
 ) {
-    // Spawn 5 enemies at different positions (all at z=0)
-    let positions = [
-        Vec3::new(3.0, 0.0, 0.0),
-        Vec3::new(3.0, 2.0, 0.0),
-        Vec3::new(3.0, -2.0, 0.0),
-        Vec3::new(3.0, 1.0, 0.0),
-        Vec3::new(3.0, -1.0, 0.0),
-    ];
-
-    for position in positions.iter() {
-        spawn_drone(&mut commands, &asset_server, &mut scene_spawner, *position);
+    // Spawn 5 enemies in a column near the right edge (all at z=0), spread over the screen's height
+    let x = play_area.right() - 0.7;
+    let half_height = (play_area.half_size.y - 0.1).max(0.0); // Keep enemies fully on screen
+    let height_fractions = [0.0, 1.0, -1.0, 0.5, -0.5];
+
+    for fraction in height_fractions {
+        let position = Vec3::new(x, play_area.center.y + fraction * half_height, 0.0);
+        spawn_drone(
+            &mut commands,
+            &enemy_assets,
+            &mut pool,
+            &asset_server,
+            &mut scene_spawner,
+            position,
+            PatternKind::Linear,
+        );
     }
 }
 
-pub fn update_enemy_colors(
-    enemies: Query<(&Collidable, &MeshMaterial3d<StandardMaterial>), With<Enemy>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-) {
-    for (collidable, material_handle) in enemies.iter() {
-        // Calculate color based on hit points
-        // Green when healthy (max HP), red when dead (0 HP)
-        let health_ratio = (collidable.hit_points / collidable.max_hit_points).clamp(0.0, 1.0);
-        let red = 1.0 - health_ratio;
-        let green = health_ratio;
-
-        // Update the material color
-        if let Some(material) = materials.get_mut(&material_handle.0) {
-            material.base_color = Color::srgb(red, green, 0.0);
-        }
+/// Spawn a random kind of enemy at the given position
+pub fn spawn_random_enemy(
+    commands: &mut Commands,
+    enemy_assets: &Res<EnemyAssets>,
+    pool: &mut ResMut<EntityPool>,
+    asset_server: &Res<AssetServer>,
+    scene_spawner: &mut ResMut<SceneSpawner>,
+    position: Vec3,
+) -> Entity {
+    use rand::Rng;
+    let mut rng = rand::thread_rng();
+
+    // Drones make half of the enemies, the other kinds share the other half
+    match rng.gen_range(0..8) {
+        0 => spawn_kamikaze(commands, enemy_assets, pool, position),
+        1 => spawn_turret(
+            commands,
+            enemy_assets,
+            pool,
+            asset_server,
+            scene_spawner,
+            position,
+        ),
+        2 => spawn_asteroid(
+            commands,
+            enemy_assets,
+            pool,
+            position,
+            Vec3::new(-0.3, 0.0, 0.0),
+            ASTEROID_MAX_SIZE,
+        ),
+        3 => spawn_formation(
+            commands,
+            enemy_assets,
+            pool,
+            asset_server,
+            scene_spawner,
+            position,
+            random_formation_shape(),
+            4,
+        ),
+        _ => spawn_drone(
+            commands,
+            enemy_assets,
+            pool,
+            asset_server,
+            scene_spawner,
+            position,
+            PatternKind::random(),
+        ),
     }
 }
 
 pub fn despawn_dead_enemies(
     mut commands: Commands,
+    enemy_assets: Res<EnemyAssets>,
+    mut pool: ResMut<EntityPool>,
     asset_server: Res<AssetServer>,
     mut scene_spawner: ResMut<SceneSpawner>,
-    enemies: Query<(Entity, &Enemy, &Collidable, &Transform), (With<Enemy>, Without<Projectile>)>,
+    enemies: Query<
+        (Entity, &Enemy, &Collidable, &Transform, Option<&Pooled>),
+        (With<Enemy>, Without<Projectile>),
+    >,
     mut player_score: ResMut<PlayerScore>,
+    mut combo: ResMut<Combo>,
+    mut enemy_destroyed: MessageWriter<EnemyDestroyed>,
+    mut bursts: MessageWriter<ParticleBurst>,
+    mut camera_shake: ResMut<CameraShake>,
+    play_area: Res<PlayArea>,
 ) {
-    for (entity, enemy, collidable, _transform) in enemies.iter() {
+    let spawn_margin = 0.5; // Replacements enter from just off the right edge
+
+    for (entity, enemy, collidable, transform, pooled) in enemies.iter() {
+        // Already removed this frame, e.g. for leaving the screen
+        if pool.is_released(entity) {
+            continue;
+        }
         if collidable.hit_points <= 0.0 {
-            // Add score to player
-            player_score.score += enemy.score;
+            // Add score to player, kills worth nothing (like detonating kamikazes) do not count for combos
+            if enemy.score > 0 {
+                let multiplier = combo.register_kill();
+                let score = enemy.score * multiplier;
+                player_score.score += score;
+                enemy_destroyed.write(EnemyDestroyed {
+                    position: transform.translation,
+                    score,
+                    multiplier,
+                });
+            }
+
+            // Blow the wreck up, bigger enemies make bigger explosions
+            bursts.write(ParticleBurst::new(
+                transform.translation,
+                ParticleEmitter::explosion().scaled(enemy.size),
+            ));
+            bursts.write(ParticleBurst::new(
+                transform.translation,
+                ParticleEmitter::debris().scaled(enemy.size),
+            ));
+            camera_shake.add_trauma(0.1 * enemy.size);
 
             // Spawn a new enemy at a random position on the right side
-            use rand::Rng;
-            let mut rng = rand::thread_rng();
-            let new_y = rng.gen_range(-2.0..2.0);
-            let new_position = Vec3::new(3.0, new_y, 0.0);
-
-            spawn_drone(
-                &mut commands,
-                &asset_server,
-                &mut scene_spawner,
-                new_position,
-            );
+            if enemy.respawn {
+                spawn_random_enemy(
+                    &mut commands,
+                    &enemy_assets,
+                    &mut pool,
+                    &asset_server,
+                    &mut scene_spawner,
+                    play_area.spawn_position(spawn_margin),
+                );
+            }
 
             // Despawn the dead enemy
-            commands.entity(entity).despawn();
+            despawn_or_release(&mut commands, &mut pool, entity, pooled);
         }
     }
 }
 
 pub fn despawn_out_of_bounds_enemies(
     mut commands: Commands,
+    enemy_assets: Res<EnemyAssets>,
+    mut pool: ResMut<EntityPool>,
     asset_server: Res<AssetServer>,
     mut scene_spawner: ResMut<SceneSpawner>,
-    enemies: Query<(Entity, &Transform), (With<Enemy>, Without<Projectile>)>,
+    enemies: Query<(Entity, &Enemy, &Transform, Option<&Pooled>), Without<Projectile>>,
+    play_area: Res<PlayArea>,
 ) {
-    let left_boundary = -5.0; // Despawn enemies that go too far to the left
+    let despawn_margin = 1.0; // Distance off-screen at which enemies are gone for good
+    let spawn_margin = 0.5; // Replacements enter from just off the right edge
 
-    for (entity, transform) in enemies.iter() {
+    for (entity, enemy, transform, pooled) in enemies.iter() {
+        // Already removed this frame, e.g. for dying
+        if pool.is_released(entity) {
+            continue;
+        }
         let pos = transform.translation;
 
-        // If enemy has moved off-screen to the left, despawn and respawn
-        if pos.x < left_boundary {
+        // Enemies enter from the right, so only leaving by another edge counts
+        let left_screen = pos.x < play_area.left() - despawn_margin
+            || pos.y > play_area.top() + despawn_margin
+            || pos.y < play_area.bottom() - despawn_margin;
+
+        // If enemy has left the screen, despawn and respawn
+        if left_screen {
             // Spawn a new enemy at a random position on the right side
-            use rand::Rng;
-            let mut rng = rand::thread_rng();
-            let new_y = rng.gen_range(-2.0..2.0);
-            let new_position = Vec3::new(3.0, new_y, 0.0);
-
-            spawn_drone(
-                &mut commands,
-                &asset_server,
-                &mut scene_spawner,
-                new_position,
-            );
+            if enemy.respawn {
+                spawn_random_enemy(
+                    &mut commands,
+                    &enemy_assets,
+                    &mut pool,
+                    &asset_server,
+                    &mut scene_spawner,
+                    play_area.spawn_position(spawn_margin),
+                );
+            }
 
             // Despawn the out-of-bounds enemy
-            commands.entity(entity).despawn();
+            despawn_or_release(&mut commands, &mut pool, entity, pooled);
         }
     }
 }
 
+/// Stop behaviour-driven movement from leaving the screen anywhere but on the left
+fn keep_on_screen(play_area: &PlayArea, position: Vec3, velocity: Vec3) -> Vec3 {
+    let inset = 0.1; // Distance kept from the edges of the screen
+
+    let mut stopped = play_area.stop_at_edges(position, velocity, inset);
+    // Leaving on the left is allowed, enemies are despawned there
+    if velocity.x < 0.0 {
+        stopped.x = velocity.x;
+    }
+    stopped
+}
+
+/// Evaluate enemy behaviour trees, then move and fire as their blackboards ask
 pub fn enemy_behavior(
-    mut query: Query<(Entity, &Enemy, &mut Weapon)>,
-    transforms: Query<&Transform>,
-    velocities: Query<&Velocity>,
-    collidables: Query<&Collidable>,
+    mut enemies: Query<
+        (
+            &mut BehaviorTree,
+            &mut Transform,
+            &mut Velocity,
+            &Collidable,
+            Option<&mut Weapon>,
+        ),
+        With<Enemy>,
+    >,
+    spatial_index: Res<SpatialIndex>,
+    play_area: Res<PlayArea>,
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    projectile_assets: Res<ProjectileAssets>,
+    mut pool: ResMut<EntityPool>,
     asset_server: Res<AssetServer>,
     mut scene_spawner: ResMut<SceneSpawner>,
+    time: Res<Time>,
 ) {
-    for (entity, enemy, mut weapon) in query.iter_mut() {
-        if let Some(behave_fn) = enemy.behave {
-            behave_fn(
-                entity,
-                &mut weapon,
-                &transforms,
-                &velocities,
-                &collidables,
-                &mut commands,
-                &mut meshes,
-                &mut materials,
-                &asset_server,
-                &mut scene_spawner,
-            );
+    let sight_range = 10.0; // Enemies ignore the ship beyond this distance
+    let turn_rate = 3.0; // Radians per second, for enemies turning to face the ship
+
+    for (mut tree, mut transform, mut velocity, collidable, weapon) in enemies.iter_mut() {
+        let blackboard = &mut tree.blackboard;
+        blackboard.position = transform.translation;
+        blackboard.health_ratio =
+            (collidable.hit_points / collidable.max_hit_points).clamp(0.0, 1.0);
+        blackboard.ship_position = spatial_index
+            .nearest_in_radius(transform.translation, sight_range, SpatialKind::Ship)
+            .map(|ship| ship.position);
+        blackboard.age += time.delta_secs();
+
+        tree.tick();
+
+        if let Some(desired_velocity) = tree.blackboard.desired_velocity {
+            velocity.linvel = keep_on_screen(&play_area, transform.translation, desired_velocity);
+        }
+
+        if let Some(model_rotation) = tree.model_rotation {
+            let target_rotation = tree.blackboard.facing.map_or(model_rotation, |facing| {
+                Quat::from_rotation_arc(Vec3::NEG_X, facing) * model_rotation
+            });
+            transform.rotation = transform
+                .rotation
+                .rotate_towards(target_rotation, turn_rate * time.delta_secs());
+        }
+
+        if tree.blackboard.wants_to_fire {
+            if let Some(mut weapon) = weapon {
+                fire_weapon_from(
+                    &mut weapon,
+                    &transform,
+                    velocity.linvel,
+                    collidable.team,
+                    &mut commands,
+                    &projectile_assets,
+                    &mut pool,
+                    &asset_server,
+                    &mut scene_spawner,
+                );
+            }
         }
     }
 }
 
+pub fn aim_enemy_weapons(
+    mut enemies: Query<(&Transform, &mut Weapon, &AimAtShip), With<Enemy>>,
+    spatial_index: Res<SpatialIndex>,
+) {
+    for (transform, mut weapon, aim) in enemies.iter_mut() {
+        let base_world_rotation = transform.rotation * aim.base_rotation;
+        let Some(base_direction) =
+            (base_world_rotation * weapon.projectile_spawn_speed_vector).try_normalize()
+        else {
+            continue;
+        };
+
+        let ship = spatial_index.nearest_in_cone(
+            transform.translation,
+            base_direction,
+            aim.cone_half_angle,
+            aim.range,
+            SpatialKind::Ship,
+        );
+
+        weapon.weapon_rotation = match ship {
+            Some(ship) => {
+                let ship_direction =
+                    (ship.position - transform.translation).normalize_or(base_direction);
+                // Turn the resting direction onto the ship, relative to the enemy's own rotation
+                transform.rotation.inverse()
+                    * Quat::from_rotation_arc(base_direction, ship_direction)
+                    * base_world_rotation
+            }
+            None => aim.base_rotation,
+        };
+    }
+}
+
 pub struct EnemiesPlugin;
 
 impl Plugin for EnemiesPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup_enemies).add_systems(
-            Update,
-            (
-                update_enemy_colors,
-                despawn_dead_enemies,
-                despawn_out_of_bounds_enemies,
-                enemy_behavior,
-            ),
-        );
+        app.init_resource::<BossSpawner>()
+            .add_message::<EnemyDestroyed>()
+            .add_systems(Startup, setup_enemy_assets)
+            // The play area is first computed once the camera exists
+            .add_systems(PostStartup, setup_enemies.after(update_play_area))
+            .add_systems(
+                Update,
+                (
+                    update_damage_feedback,
+                    // Enemies must score and respawn before the generic collidable cleanup recycles them
+                    despawn_dead_enemies.before(despawn_dead_collidable),
+                    despawn_out_of_bounds_enemies,
+                    aim_enemy_weapons.before(enemy_behavior),
+                    // Behaviour trees may override the velocity set by movement patterns
+                    enemy_behavior.after(follow_movement_patterns),
+                    steer_kamikazes,
+                    follow_movement_patterns,
+                    reform_formations.before(hold_formation_slots),
+                    hold_formation_slots,
+                    track_turrets.before(enemy_behavior),
+                    detonate_kamikazes.before(despawn_dead_enemies),
+                    split_dying_enemies.before(despawn_dead_enemies),
+                    spawn_boss_at_score,
+                    update_boss_phases.before(boss_attack),
+                    move_bosses,
+                    boss_attack,
+                    warp_after_boss_defeat.before(despawn_dead_enemies),
+                ),
+            );
     }
 }
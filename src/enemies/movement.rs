@@ -0,0 +1,186 @@
+use crate::enemies::Enemy;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use rand::Rng;
+use std::f32::consts::TAU;
+
+/// A parametric path, as an offset from the straight drift of a MovementPattern
+#[derive(Clone, Debug)]
+pub enum PatternKind {
+    /// No offset, the enemy only drifts
+    Linear,
+    /// Smooth vertical waves
+    Sine { amplitude: f32, frequency: f32 },
+    /// Vertical zig-zag at constant speed, `period` seconds for a full zig and zag
+    ZigZag { amplitude: f32, period: f32 },
+    /// Circles starting at the spawn position, counter-clockwise for a positive angular speed
+    Orbit { radius: f32, angular_speed: f32 },
+    /// Bézier curve over `duration` seconds, its first control point is the spawn position
+    /// (Vec3::ZERO), see PatternKind::bezier
+    Bezier { points: Vec<Vec3>, duration: f32 },
+    /// Catmull-Rom spline through every point over `duration` seconds, starting at the spawn
+    /// position (Vec3::ZERO), see PatternKind::spline
+    Spline { points: Vec<Vec3>, duration: f32 },
+}
+
+impl PatternKind {
+    /// Bézier curve from the spawn position, shaped by the control points
+    pub fn bezier(control_points: &[Vec3], duration: f32) -> Self {
+        PatternKind::Bezier {
+            points: std::iter::once(Vec3::ZERO)
+                .chain(control_points.iter().copied())
+                .collect(),
+            duration,
+        }
+    }
+
+    /// Catmull-Rom spline from the spawn position through every waypoint
+    pub fn spline(waypoints: &[Vec3], duration: f32) -> Self {
+        PatternKind::Spline {
+            points: std::iter::once(Vec3::ZERO)
+                .chain(waypoints.iter().copied())
+                .collect(),
+            duration,
+        }
+    }
+
+    /// Offset from the drift position at the given time since the pattern started
+    pub fn offset_at(&self, time: f32) -> Vec3 {
+        match self {
+            PatternKind::Linear => Vec3::ZERO,
+            PatternKind::Sine {
+                amplitude,
+                frequency,
+            } => Vec3::Y * amplitude * (TAU * frequency * time).sin(),
+            PatternKind::ZigZag { amplitude, period } => {
+                // Triangle wave starting at 0 and going up first
+                let phase = time / period + 0.25;
+                let triangle = 4.0 * (phase - (phase + 0.5).floor()).abs() - 1.0;
+                Vec3::Y * amplitude * triangle
+            }
+            PatternKind::Orbit {
+                radius,
+                angular_speed,
+            } => {
+                let angle = angular_speed * time;
+                Vec3::new(radius * (angle.cos() - 1.0), radius * angle.sin(), 0.0)
+            }
+            PatternKind::Bezier { points, duration } => {
+                bezier(points, (time / duration).clamp(0.0, 1.0))
+            }
+            PatternKind::Spline { points, duration } => {
+                catmull_rom(points, (time / duration).clamp(0.0, 1.0))
+            }
+        }
+    }
+
+    /// Pick one of the patterns used by regular waves
+    pub fn random() -> Self {
+        let mut rng = rand::thread_rng();
+        match rng.gen_range(0..6) {
+            0 => PatternKind::Linear,
+            1 => PatternKind::Sine {
+                amplitude: 0.5,
+                frequency: 0.3,
+            },
+            2 => PatternKind::ZigZag {
+                amplitude: 0.5,
+                period: 2.0,
+            },
+            3 => PatternKind::Orbit {
+                radius: 0.6,
+                angular_speed: 1.0,
+            },
+            4 => PatternKind::spline(
+                &[
+                    Vec3::new(-1.0, -1.0, 0.0),
+                    Vec3::new(-2.0, 1.0, 0.0),
+                    Vec3::new(-3.0, -0.5, 0.0),
+                ],
+                6.0,
+            ),
+            _ => PatternKind::bezier(
+                &[
+                    Vec3::new(-1.0, 1.5, 0.0),
+                    Vec3::new(-2.0, -1.5, 0.0),
+                    Vec3::new(-3.0, 0.0, 0.0),
+                ],
+                6.0,
+            ),
+        }
+    }
+}
+
+/// Evaluate a Bézier curve in Bernstein form, t from 0.0 to 1.0
+fn bezier(points: &[Vec3], t: f32) -> Vec3 {
+    let degree = points.len().saturating_sub(1);
+    let mut binomial = 1.0; // Binomial coefficient of the degree and the point index
+    let mut sum = Vec3::ZERO;
+    for (i, point) in points.iter().enumerate() {
+        sum += *point * binomial * t.powi(i as i32) * (1.0 - t).powi((degree - i) as i32);
+        binomial *= (degree - i) as f32 / (i + 1) as f32;
+    }
+    sum
+}
+
+/// Evaluate a Catmull-Rom spline passing through every point, t from 0.0 to 1.0
+fn catmull_rom(points: &[Vec3], t: f32) -> Vec3 {
+    if points.len() < 2 {
+        return points.first().copied().unwrap_or(Vec3::ZERO);
+    }
+
+    let segments = points.len() - 1;
+    let scaled = t * segments as f32;
+    let i = (scaled.floor() as usize).min(segments - 1);
+    let u = scaled - i as f32;
+
+    // The end points are repeated to get tangents at both ends
+    let p0 = points[i.saturating_sub(1)];
+    let p1 = points[i];
+    let p2 = points[i + 1];
+    let p3 = points[(i + 2).min(segments)];
+
+    0.5 * (2.0 * p1
+        + (p2 - p0) * u
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * u * u
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * u * u * u)
+}
+
+/// Drives the velocity of a kinematic enemy along a parametric path.
+/// Only the velocity is driven, so the path continues from wherever the enemy was moved to.
+#[derive(Component, Clone)]
+pub struct MovementPattern {
+    pub kind: PatternKind,
+    /// Straight drift added to the pattern's offset
+    pub drift: Vec3,
+    /// Time since the pattern started
+    pub time: f32,
+}
+
+impl MovementPattern {
+    pub fn new(kind: PatternKind, drift: Vec3) -> Self {
+        Self {
+            kind,
+            drift,
+            time: 0.0,
+        }
+    }
+}
+
+/// Set enemy velocities to their pattern's average velocity over the frame
+pub fn follow_movement_patterns(
+    mut movers: Query<(&mut MovementPattern, &mut Velocity), With<Enemy>>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_secs();
+    if delta <= 0.0 {
+        return;
+    }
+
+    for (mut pattern, mut velocity) in movers.iter_mut() {
+        let start = pattern.kind.offset_at(pattern.time);
+        pattern.time += delta;
+        let end = pattern.kind.offset_at(pattern.time);
+        velocity.linvel = (end - start) / delta + pattern.drift;
+    }
+}
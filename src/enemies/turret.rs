@@ -0,0 +1,165 @@
+use crate::collision::{Collidable, Team};
+use crate::enemies::ai::BehaviorTree;
+use crate::enemies::damage_feedback::DamageFeedback;
+use crate::enemies::{Enemy, EnemyAssets};
+use crate::pool::{EntityPool, PoolKind, acquire_or_spawn};
+use crate::projectiles::guidance::{intercept_point, rotate_toward};
+use crate::projectiles::spawn_flak_projectile;
+use crate::ship::Ship;
+use crate::spatial::{SpatialIndex, SpatialKind};
+use crate::weapons::weapon::{Weapon, WeaponMesh, attach_weapon};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// A slowly drifting enemy whose weapon turns toward the ship
+#[derive(Component, Clone)]
+pub struct Turret {
+    pub turn_rate: f32, // Radians per second
+    pub range: f32,     // Distance at which the turret starts tracking the ship
+}
+
+impl Default for Turret {
+    fn default() -> Self {
+        Self {
+            turn_rate: 1.0,
+            range: 8.0,
+        }
+    }
+}
+
+/// Creates the turret's flak gun, firing bursts of 4 shells
+pub fn create_turret_weapon() -> Weapon {
+    Weapon::new()
+        .with_fire_cooldown(0.15)
+        .with_burst(4, 2.0) // 4 shots, then wait 2 seconds
+        .with_projectile_spawner(spawn_flak_projectile)
+        .with_projectile_spawn_offset(Vec3::new(0.3, 0.0, 0.0)) // Spawn at the end of the barrel
+        .with_projectile_spawn_speed_vector(Vec3::new(2.0, 0.0, 0.0))
+}
+
+pub fn spawn_turret(
+    commands: &mut Commands,
+    enemy_assets: &Res<EnemyAssets>,
+    pool: &mut ResMut<EntityPool>,
+    asset_server: &Res<AssetServer>,
+    scene_spawner: &mut ResMut<SceneSpawner>,
+    position: Vec3,
+) -> Entity {
+    // Turrets drift slowly to the left
+    let drift_velocity = Vec3::new(-0.1, 0.0, 0.0);
+
+    let (turret_entity, fresh) = acquire_or_spawn(
+        commands,
+        pool,
+        PoolKind::Turret,
+        (
+            Enemy {
+                score: 200,
+                respawn: true,
+                size: 1.3,
+            },
+            // Turrets fire whenever the ship is in range, the weapon pauses between bursts
+            BehaviorTree::turret(Turret::default().range),
+            DamageFeedback::default(),
+            Turret::default(),
+            Collidable::new(20.0, 60.0, Team::Enemy), // Sturdier than drones
+            Velocity::linear(drift_velocity),
+            Transform::from_translation(position),
+        ),
+    );
+
+    // The weapon fires along its X axis, start with the barrel pointing left
+    let mut weapon = create_turret_weapon();
+    let weapon_rotation = Quat::from_rotation_z(std::f32::consts::PI);
+
+    if fresh {
+        commands.entity(turret_entity).insert((
+            RigidBody::KinematicVelocityBased,
+            Collider::ball(0.15),
+            ActiveEvents::COLLISION_EVENTS,
+            ActiveCollisionTypes::KINEMATIC_KINEMATIC,
+            Mesh3d(enemy_assets.turret_base_mesh.clone()),
+            MeshMaterial3d(enemy_assets.turret_material.clone()),
+        ));
+
+        // The barrel hangs from a pivot rotating with the weapon
+        let barrel_pivot = commands
+            .spawn((
+                WeaponMesh {
+                    rotation_offset: Quat::IDENTITY,
+                },
+                Transform::from_rotation(weapon_rotation),
+                Visibility::default(),
+            ))
+            .with_child((
+                Mesh3d(enemy_assets.turret_barrel_mesh.clone()),
+                MeshMaterial3d(enemy_assets.turret_material.clone()),
+                Transform {
+                    translation: Vec3::new(0.15, 0.0, 0.0),
+                    rotation: Quat::from_rotation_z(-std::f32::consts::FRAC_PI_2), // Cylinders stand along Y
+                    scale: Vec3::ONE,
+                },
+            ))
+            .id();
+        commands.entity(turret_entity).add_child(barrel_pivot);
+
+        attach_weapon(
+            commands,
+            asset_server,
+            scene_spawner,
+            turret_entity,
+            weapon,
+            weapon_rotation,
+            Vec3::ONE,
+        );
+    } else {
+        // Recycled turrets keep their barrel, only the weapon needs resetting
+        weapon.weapon_rotation = weapon_rotation;
+        commands.entity(turret_entity).insert(weapon);
+    }
+
+    turret_entity
+}
+
+/// Turn turret weapons toward where the ship will be, limited by the turret's turn rate
+pub fn track_turrets(
+    mut turrets: Query<(&Transform, &Turret, &mut Weapon), With<Enemy>>,
+    ships: Query<&Velocity, With<Ship>>,
+    spatial_index: Res<SpatialIndex>,
+    time: Res<Time>,
+) {
+    for (transform, turret, mut weapon) in turrets.iter_mut() {
+        let Some(ship) =
+            spatial_index.nearest_in_radius(transform.translation, turret.range, SpatialKind::Ship)
+        else {
+            continue;
+        };
+
+        let weapon_world_rotation = transform.rotation * weapon.weapon_rotation;
+        let Some(current_direction) =
+            (weapon_world_rotation * weapon.projectile_spawn_speed_vector).try_normalize()
+        else {
+            continue;
+        };
+
+        // Lead the ship so slow shells still have a chance to hit
+        let ship_velocity = ships
+            .get(ship.entity)
+            .map_or(Vec3::ZERO, |velocity| velocity.linvel);
+        let aim_point = intercept_point(
+            transform.translation,
+            weapon.projectile_spawn_speed_vector.length(),
+            ship.position,
+            ship_velocity,
+        );
+        let direction = rotate_toward(
+            current_direction,
+            aim_point - transform.translation,
+            turret.turn_rate * time.delta_secs(),
+        );
+
+        weapon.weapon_rotation = transform.rotation.inverse()
+            * Quat::from_rotation_arc(current_direction, direction)
+            * weapon_world_rotation;
+    }
+}
@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::f32::consts::FRAC_PI_2;
+
+/// Primitive stand-ins for glTF models, in world units
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum FallbackShape {
+    Ship,   // Cone pointing forward
+    Drone,  // Ball
+    Rocket, // Thin capsule along the rocket's axis
+    Cannon, // Barrel, weapon meshes have no collider
+}
+
+impl FallbackShape {
+    pub fn mesh(&self) -> Mesh {
+        match self {
+            FallbackShape::Ship => Cone {
+                radius: 0.12,
+                height: 0.35,
+            }
+            .into(),
+            FallbackShape::Drone => Sphere::new(0.12).into(),
+            FallbackShape::Rocket => Capsule3d::new(0.02, 0.1).into(),
+            FallbackShape::Cannon => Cylinder::new(0.03, 0.2).into(),
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            FallbackShape::Ship => Color::srgb(0.6, 0.7, 0.9),
+            FallbackShape::Drone => Color::srgb(0.8, 0.2, 0.2),
+            FallbackShape::Rocket => Color::srgb(1.0, 0.6, 0.2),
+            FallbackShape::Cannon => Color::srgb(0.5, 0.5, 0.5),
+        }
+    }
+
+    /// Rotation of the primitive inside the model's space
+    pub fn rotation(&self) -> Quat {
+        match self {
+            FallbackShape::Ship => Quat::from_rotation_z(-FRAC_PI_2), // Tip toward +X
+            FallbackShape::Drone => Quat::IDENTITY,
+            FallbackShape::Rocket => Quat::from_rotation_x(FRAC_PI_2), // Along the rocket's Z axis
+            FallbackShape::Cannon => Quat::from_rotation_z(FRAC_PI_2),
+        }
+    }
+
+    /// Collider matching the mesh, replacing the one AsyncSceneCollider would have built
+    pub fn collider(&self) -> Option<Collider> {
+        match self {
+            FallbackShape::Ship => Some(Collider::cone(0.175, 0.12)),
+            FallbackShape::Drone => Some(Collider::ball(0.12)),
+            FallbackShape::Rocket => Some(Collider::capsule_y(0.05, 0.02)),
+            FallbackShape::Cannon => None,
+        }
+    }
+}
+
+/// Replaces the entity's SceneRoot (and AsyncSceneCollider) with a primitive if the scene fails to load
+#[derive(Component, Clone, Copy)]
+pub struct SceneFallback(pub FallbackShape);
+
+/// Fallback meshes and materials created so far, and the models already warned about
+#[derive(Resource, Default)]
+pub struct FallbackAssets {
+    pub handles: HashMap<FallbackShape, (Handle<Mesh>, Handle<StandardMaterial>)>,
+    pub warned_paths: HashSet<String>,
+}
+
+/// Whether a scene failed to load, looking at the file too since a labeled scene of a missing file
+/// may not be marked as failed itself
+fn scene_failed(asset_server: &AssetServer, scene: &Handle<Scene>) -> bool {
+    if asset_server.load_state(scene.id()).is_failed() {
+        return true;
+    }
+    scene
+        .path()
+        .and_then(|path| asset_server.get_handle_untyped(path.without_label()))
+        .is_some_and(|file| asset_server.load_state(file.id()).is_failed())
+}
+
+/// Swap scenes that failed to load for their primitive fallback, warning once per model
+pub fn apply_scene_fallbacks(
+    mut commands: Commands,
+    scenes: Query<(Entity, &SceneRoot, &SceneFallback, &GlobalTransform)>,
+    asset_server: Res<AssetServer>,
+    mut fallback_assets: ResMut<FallbackAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (entity, scene_root, fallback, global_transform) in scenes.iter() {
+        if asset_server.load_state(scene_root.0.id()).is_loaded() {
+            // The model is there, nothing more to check
+            commands.entity(entity).remove::<SceneFallback>();
+            continue;
+        }
+        if !scene_failed(&asset_server, &scene_root.0) {
+            continue; // Still loading
+        }
+
+        let path = scene_root
+            .0
+            .path()
+            .map_or_else(|| "<unnamed scene>".to_string(), |path| path.to_string());
+        if fallback_assets.warned_paths.insert(path.clone()) {
+            warn!(
+                "Could not load {}, using a {:?} primitive instead",
+                path, fallback.0
+            );
+        }
+
+        let shape = fallback.0;
+        let (mesh, material) = fallback_assets
+            .handles
+            .entry(shape)
+            .or_insert_with(|| {
+                (
+                    meshes.add(shape.mesh()),
+                    materials.add(StandardMaterial {
+                        base_color: shape.color(),
+                        ..default()
+                    }),
+                )
+            })
+            .clone();
+
+        // Models are scaled down to fit their scene, undo that so the primitive is in world units
+        let entity_scale = global_transform
+            .compute_transform()
+            .scale
+            .max(Vec3::splat(1e-6));
+        let mut primitive = commands.spawn((
+            Mesh3d(mesh),
+            MeshMaterial3d(material),
+            Transform {
+                translation: Vec3::ZERO,
+                rotation: shape.rotation(),
+                scale: Vec3::ONE / entity_scale,
+            },
+        ));
+        if let Some(collider) = shape.collider() {
+            primitive.insert(collider);
+        }
+        let primitive_entity = primitive.id();
+
+        commands
+            .entity(entity)
+            .remove::<(SceneRoot, AsyncSceneCollider, SceneFallback)>()
+            .add_child(primitive_entity);
+    }
+}
+
+pub struct FallbackPlugin;
+
+impl Plugin for FallbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FallbackAssets>()
+            .add_systems(Update, apply_scene_fallbacks);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::collision::Collidable;
+use crate::enemies::Enemy;
+use crate::enemies::boss::Boss;
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+/// Seconds a health bar stays fully visible after the enemy was last damaged
+pub const ENEMY_HEALTH_BAR_VISIBLE_TIME: f32 = 3.0;
+/// Seconds a health bar takes to fade out afterwards
+pub const ENEMY_HEALTH_BAR_FADE_TIME: f32 = 0.5;
+
+/// A small health bar UI node following a damaged enemy on screen
+#[derive(Component)]
+pub struct EnemyHealthBar {
+    pub target: Entity,
+    pub fill: Entity,
+    /// Hit points of the target last frame, to detect new damage
+    pub last_hit_points: f32,
+    /// Time since the target was last damaged
+    pub idle_time: f32,
+}
+
+#[derive(Component)]
+pub struct EnemyHealthBarFill;
+
+/// Spawn a health bar for every damaged enemy that does not have one yet.
+/// Bosses already have their own bar at the top of the screen.
+pub fn spawn_enemy_health_bars(
+    mut commands: Commands,
+    enemies: Query<(Entity, &Collidable), (With<Enemy>, Without<Boss>)>,
+    health_bars: Query<&EnemyHealthBar>,
+) {
+    let enemies_with_bars: HashSet<Entity> = health_bars
+        .iter()
+        .map(|health_bar| health_bar.target)
+        .collect();
+
+    for (enemy_entity, collidable) in enemies.iter() {
+        if !collidable.is_alive()
+            || collidable.hit_points >= collidable.max_hit_points
+            || enemies_with_bars.contains(&enemy_entity)
+        {
+            continue;
+        }
+
+        let fill_entity = commands
+            .spawn((
+                EnemyHealthBarFill,
+                Node {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(1.0, 0.0, 0.0)), // Red fill, like the player's bar
+            ))
+            .id();
+
+        commands
+            .spawn((
+                EnemyHealthBar {
+                    target: enemy_entity,
+                    fill: fill_entity,
+                    last_hit_points: collidable.hit_points,
+                    idle_time: 0.0,
+                },
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: px(40),
+                    height: px(5),
+                    display: Display::None, // Shown once positioned over the enemy
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.2, 0.2, 0.2)), // Dark gray background
+            ))
+            .add_child(fill_entity);
+    }
+}
+
+/// Keep enemy health bars over their enemy, and fade them out when the enemy stops taking damage
+pub fn update_enemy_health_bars(
+    mut commands: Commands,
+    mut health_bars: Query<(Entity, &mut EnemyHealthBar, &mut Node, &mut BackgroundColor)>,
+    mut health_bar_fills: Query<
+        (&mut Node, &mut BackgroundColor),
+        (With<EnemyHealthBarFill>, Without<EnemyHealthBar>),
+    >,
+    enemies: Query<(&Collidable, &GlobalTransform), With<Enemy>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    time: Res<Time>,
+) {
+    let Ok((camera, camera_global_transform)) = camera_query.single() else {
+        return;
+    };
+    let bar_height_above_enemy = 0.3; // World units above the enemy's center
+
+    for (bar_entity, mut health_bar, mut node, mut background_color) in health_bars.iter_mut() {
+        // Enemies that died, left or healed back to full health lose their bar
+        let target = enemies
+            .get(health_bar.target)
+            .ok()
+            .filter(|(collidable, _)| {
+                collidable.is_alive() && collidable.hit_points < collidable.max_hit_points
+            });
+        let Some((collidable, enemy_transform)) = target else {
+            commands.entity(bar_entity).despawn();
+            continue;
+        };
+
+        if collidable.hit_points < health_bar.last_hit_points {
+            health_bar.idle_time = 0.0;
+        } else {
+            health_bar.idle_time += time.delta_secs();
+        }
+        health_bar.last_hit_points = collidable.hit_points;
+
+        let fade = (health_bar.idle_time - ENEMY_HEALTH_BAR_VISIBLE_TIME).max(0.0)
+            / ENEMY_HEALTH_BAR_FADE_TIME;
+        if fade >= 1.0 {
+            // Keep the faded bar hidden, so it shows again on the next hit
+            node.display = Display::None;
+            continue;
+        }
+        let alpha = 1.0 - fade;
+
+        // Center the bar above the enemy on screen
+        let world_position = enemy_transform.translation() + Vec3::Y * bar_height_above_enemy;
+        match camera.world_to_viewport(camera_global_transform, world_position) {
+            Ok(viewport_position) => {
+                node.display = Display::Flex;
+                node.left = px(viewport_position.x - 20.0);
+                node.top = px(viewport_position.y);
+            }
+            Err(_) => node.display = Display::None,
+        }
+        background_color.0 = background_color.0.with_alpha(alpha);
+
+        if let Ok((mut fill_node, mut fill_color)) = health_bar_fills.get_mut(health_bar.fill) {
+            let health_percentage =
+                (collidable.hit_points / collidable.max_hit_points).clamp(0.0, 1.0);
+            fill_node.width = Val::Percent(health_percentage * 100.0);
+            fill_color.0 = fill_color.0.with_alpha(alpha);
+        }
+    }
+}
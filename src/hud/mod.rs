@@ -0,0 +1,348 @@
+pub mod enemy_health_bars;
+pub mod popups;
+
+use crate::collision::Collidable;
+use crate::enemies::boss::{Boss, BossPhase};
+use crate::ship::{PlayerLives, Ship};
+use bevy::prelude::*;
+use enemy_health_bars::{spawn_enemy_health_bars, update_enemy_health_bars};
+use popups::{spawn_damage_popups, spawn_score_popups, update_popups};
+
+#[derive(Component)]
+pub struct ScoreDisplay;
+
+#[derive(Component)]
+pub struct LivesDisplay;
+
+#[derive(Component)]
+pub struct HealthBar;
+
+#[derive(Component)]
+pub struct HealthBarFill;
+
+/// The boss bar container, sliding in from the top of the screen while a boss is alive
+#[derive(Component)]
+pub struct BossBar {
+    /// 0.0 when hidden above the screen, 1.0 when fully shown
+    pub slide: f32,
+}
+
+#[derive(Component)]
+pub struct BossBarFill;
+
+#[derive(Component)]
+pub struct BossBarLabel;
+
+/// A marker showing one of the boss phases below the boss bar
+#[derive(Component)]
+pub struct BossPhaseMarker(pub BossPhase);
+
+#[derive(Resource)]
+pub struct PlayerScore {
+    pub score: u32,
+}
+
+impl Default for PlayerScore {
+    fn default() -> Self {
+        Self { score: 0 }
+    }
+}
+
+/// Seconds between kills for the combo to keep going
+pub const COMBO_WINDOW: f32 = 2.0;
+
+/// Kills in quick succession raise the score multiplier
+#[derive(Resource, Default)]
+pub struct Combo {
+    pub count: u32,
+    pub timer: f32, // Time left before the combo ends
+}
+
+impl Combo {
+    /// Score multiplier, one more for every 5 kills in the combo, up to 4
+    pub fn multiplier(&self) -> u32 {
+        (1 + self.count / 5).min(4)
+    }
+
+    /// Count a kill and return the multiplier it scores with
+    pub fn register_kill(&mut self) -> u32 {
+        let multiplier = self.multiplier();
+        self.count += 1;
+        self.timer = COMBO_WINDOW;
+        multiplier
+    }
+}
+
+pub fn update_combo(mut combo: ResMut<Combo>, time: Res<Time>) {
+    if combo.count == 0 {
+        return;
+    }
+    combo.timer -= time.delta_secs();
+    if combo.timer <= 0.0 {
+        combo.count = 0;
+    }
+}
+
+pub fn setup_hud(mut commands: Commands) {
+    // Text used to show controls
+    commands.spawn((
+        Text::new("CONTROLS:\nZQSD - Move\nQ/E - Rotate\n1/2/3 - Switch Weapons\nSPACE - Fire\nK - Toggle Screen Shake"),
+        TextFont {
+            font_size: 20.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: px(12),
+            left: px(12),
+            ..default()
+        },
+    ));
+
+    // Score display
+    commands.spawn((
+        ScoreDisplay,
+        Text::new("Score: 0"),
+        TextFont {
+            font_size: 32.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: px(12),
+            right: px(12),
+            ..default()
+        },
+    ));
+
+    // Lives display
+    commands.spawn((
+        LivesDisplay,
+        Text::new("Lives: 100"),
+        TextFont {
+            font_size: 32.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            top: px(12),
+            right: px(12),
+            ..default()
+        },
+    ));
+
+    // Health bar container
+    commands
+        .spawn((
+            HealthBar,
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: px(20),
+                left: Val::Percent(50.0),
+                width: px(200),
+                height: px(20),
+                margin: UiRect::left(Val::Px(-100.0)), // Center the bar
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.2, 0.2, 0.2)), // Dark gray background
+        ))
+        .with_children(|parent| {
+            // Health bar fill (red)
+            parent.spawn((
+                HealthBarFill,
+                Node {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(1.0, 0.0, 0.0)), // Red fill
+            ));
+        });
+
+    // Boss bar container, hidden until a boss spawns
+    commands
+        .spawn((
+            BossBar { slide: 0.0 },
+            Node {
+                position_type: PositionType::Absolute,
+                top: px(-80),
+                left: Val::Percent(50.0),
+                width: px(400),
+                margin: UiRect::left(Val::Px(-200.0)), // Center the bar
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: px(4),
+                display: Display::None,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            // Boss name and current phase
+            parent.spawn((
+                BossBarLabel,
+                Text::new("BOSS"),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+
+            // Aggregate hit points of the core and its parts
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Percent(100.0),
+                        height: px(16),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.2, 0.2, 0.2)), // Dark gray background
+                ))
+                .with_children(|bar| {
+                    bar.spawn((
+                        BossBarFill,
+                        Node {
+                            width: Val::Percent(100.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.6, 0.1, 0.8)), // Purple fill
+                    ));
+                });
+
+            // One marker per phase
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: px(8),
+                    ..default()
+                })
+                .with_children(|markers| {
+                    for phase in BossPhase::ALL {
+                        markers.spawn((
+                            BossPhaseMarker(phase),
+                            Node {
+                                width: px(12),
+                                height: px(12),
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgb(0.4, 0.4, 0.4)),
+                        ));
+                    }
+                });
+        });
+}
+
+pub fn update_score_display(
+    player_score: Res<PlayerScore>,
+    mut score_text_query: Query<&mut Text, With<ScoreDisplay>>,
+) {
+    for mut text in score_text_query.iter_mut() {
+        *text = Text::new(format!("Score: {}", player_score.score));
+    }
+}
+
+pub fn update_lives_display(
+    player_lives: Res<PlayerLives>,
+    mut lives_text_query: Query<&mut Text, With<LivesDisplay>>,
+) {
+    for mut text in lives_text_query.iter_mut() {
+        *text = Text::new(format!("Lives: {}", player_lives.lives));
+    }
+}
+
+pub fn update_health_bar(
+    ship_query: Query<&Collidable, With<Ship>>,
+    mut health_bar_fill_query: Query<&mut Node, With<HealthBarFill>>,
+) {
+    if let Ok(collidable) = ship_query.single() {
+        if let Ok(mut health_bar_fill_node) = health_bar_fill_query.single_mut() {
+            // Calculate health percentage
+            let health_percentage =
+                (collidable.hit_points / collidable.max_hit_points).clamp(0.0, 1.0);
+
+            // Update the width of the health bar fill
+            health_bar_fill_node.width = Val::Percent(health_percentage * 100.0);
+        }
+    }
+}
+
+/// Show the boss bar while a boss is alive, sliding it in on spawn and out on defeat
+pub fn update_boss_bar(
+    bosses: Query<&Boss>,
+    mut boss_bars: Query<(&mut BossBar, &mut Node)>,
+    mut boss_bar_fills: Query<&mut Node, (With<BossBarFill>, Without<BossBar>)>,
+    mut boss_bar_labels: Query<&mut Text, With<BossBarLabel>>,
+    mut phase_markers: Query<(&BossPhaseMarker, &mut BackgroundColor)>,
+    time: Res<Time>,
+) {
+    let slide_speed = 2.0; // The bar takes half a second to slide in or out
+    let boss = bosses.iter().next();
+
+    if let Ok((mut boss_bar, mut boss_bar_node)) = boss_bars.single_mut() {
+        // Slide toward shown while a boss is alive, toward hidden once it is defeated
+        let target = if boss.is_some() { 1.0 } else { 0.0 };
+        let step = slide_speed * time.delta_secs();
+        boss_bar.slide = if boss_bar.slide < target {
+            (boss_bar.slide + step).min(target)
+        } else {
+            (boss_bar.slide - step).max(target)
+        };
+
+        boss_bar_node.top = px(-80.0 + 92.0 * boss_bar.slide);
+        boss_bar_node.display = if boss_bar.slide > 0.0 {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+
+    let health_percentage = boss.map_or(0.0, |boss| {
+        (boss.hit_points / boss.max_hit_points).clamp(0.0, 1.0)
+    });
+    for mut fill_node in boss_bar_fills.iter_mut() {
+        fill_node.width = Val::Percent(health_percentage * 100.0);
+    }
+
+    // Keep showing the last phase while the bar slides out
+    let Some(boss) = boss else {
+        return;
+    };
+    for mut text in boss_bar_labels.iter_mut() {
+        *text = Text::new(format!("BOSS - {:?}", boss.phase).to_uppercase());
+    }
+    for (marker, mut background_color) in phase_markers.iter_mut() {
+        *background_color = if marker.0 == boss.phase {
+            BackgroundColor(Color::srgb(1.0, 0.6, 0.0)) // Current phase
+        } else if marker.0 < boss.phase {
+            BackgroundColor(Color::srgb(0.15, 0.15, 0.15)) // Phase already cleared
+        } else {
+            BackgroundColor(Color::srgb(0.4, 0.4, 0.4)) // Phase still to come
+        };
+    }
+}
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlayerScore>()
+            .init_resource::<Combo>()
+            .add_systems(Startup, setup_hud)
+            .add_systems(
+                Update,
+                (
+                    update_score_display,
+                    update_lives_display,
+                    update_health_bar,
+                    update_boss_bar,
+                    (spawn_enemy_health_bars, update_enemy_health_bars).chain(),
+                    update_combo,
+                    (spawn_damage_popups, spawn_score_popups, update_popups).chain(),
+                ),
+            );
+    }
+}
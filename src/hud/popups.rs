@@ -0,0 +1,126 @@
+use crate::collision::{DamageDealt, Team};
+use crate::enemies::EnemyDestroyed;
+use bevy::prelude::*;
+
+/// Seconds a popup stays on screen
+pub const POPUP_LIFETIME: f32 = 0.9;
+/// World units a popup rises per second
+pub const POPUP_RISE_SPEED: f32 = 0.6;
+
+/// Text rising from a point in the world and fading out
+#[derive(Component)]
+pub struct Popup {
+    pub world_position: Vec3,
+    pub age: f32,
+    pub lifetime: f32,
+    pub color: Color,
+}
+
+fn spawn_popup(
+    commands: &mut Commands,
+    world_position: Vec3,
+    text: String,
+    color: Color,
+    size: f32,
+) {
+    commands.spawn((
+        Popup {
+            world_position,
+            age: 0.0,
+            lifetime: POPUP_LIFETIME,
+            color,
+        },
+        Text::new(text),
+        TextFont {
+            font_size: size,
+            ..default()
+        },
+        TextColor(color),
+        Node {
+            position_type: PositionType::Absolute,
+            display: Display::None, // Shown once positioned over the world position
+            ..default()
+        },
+    ));
+}
+
+/// Show the damage of every hit where it landed
+pub fn spawn_damage_popups(mut commands: Commands, mut damage_dealt: MessageReader<DamageDealt>) {
+    for damage in damage_dealt.read() {
+        let color = match damage.team {
+            Team::Player => Color::srgb(1.0, 0.3, 0.3), // Damage taken by the ship
+            Team::Enemy => Color::WHITE,
+        };
+        spawn_popup(
+            &mut commands,
+            damage.position,
+            format!("{}", damage.amount.round() as i32),
+            color,
+            14.0,
+        );
+    }
+}
+
+/// Show the score of every destroyed enemy, combo kills stand out
+pub fn spawn_score_popups(
+    mut commands: Commands,
+    mut enemy_destroyed: MessageReader<EnemyDestroyed>,
+) {
+    for destroyed in enemy_destroyed.read() {
+        let (text, color, size) = if destroyed.multiplier > 1 {
+            (
+                format!("+{} x{}", destroyed.score, destroyed.multiplier),
+                Color::srgb(1.0, 0.3, 1.0), // Magenta for combos
+                22.0,
+            )
+        } else {
+            (
+                format!("+{}", destroyed.score),
+                Color::srgb(1.0, 0.85, 0.2), // Gold
+                18.0,
+            )
+        };
+        // Start a bit above the enemy so the score does not hide its damage numbers
+        spawn_popup(
+            &mut commands,
+            destroyed.position + Vec3::Y * 0.15,
+            text,
+            color,
+            size,
+        );
+    }
+}
+
+/// Move popups up over time, fade them out and despawn them at the end of their lifetime
+pub fn update_popups(
+    mut commands: Commands,
+    mut popups: Query<(Entity, &mut Popup, &mut Node, &mut TextColor)>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    time: Res<Time>,
+) {
+    let Ok((camera, camera_global_transform)) = camera_query.single() else {
+        return;
+    };
+
+    for (entity, mut popup, mut node, mut text_color) in popups.iter_mut() {
+        popup.age += time.delta_secs();
+        if popup.age >= popup.lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        popup.world_position.y += POPUP_RISE_SPEED * time.delta_secs();
+
+        match camera.world_to_viewport(camera_global_transform, popup.world_position) {
+            Ok(viewport_position) => {
+                node.display = Display::Flex;
+                node.left = px(viewport_position.x - 10.0);
+                node.top = px(viewport_position.y - 10.0);
+            }
+            Err(_) => node.display = Display::None,
+        }
+
+        // Fully opaque for the first half, then fade out
+        let alpha = (2.0 * (1.0 - popup.age / popup.lifetime)).min(1.0);
+        text_color.0 = popup.color.with_alpha(alpha);
+    }
+}
@@ -1,7 +1,15 @@
+mod camera_shake;
 mod collision;
+mod fallback;
 mod hud;
+mod nebula;
+mod noise;
+mod particles;
+mod play_area;
+mod pool;
 mod projectiles;
 mod ship;
+mod spatial;
 mod starfield;
 mod enemies;
 mod weapons;
@@ -9,10 +17,17 @@ mod weapons;
 use bevy::prelude::*;
 
 use bevy_rapier3d::prelude::*;
+use camera_shake::{CameraRig, CameraShakePlugin};
 use collision::CollisionPlugin;
+use fallback::FallbackPlugin;
 use hud::HudPlugin;
+use nebula::NebulaPlugin;
+use particles::ParticlesPlugin;
+use play_area::PlayAreaPlugin;
+use pool::PoolPlugin;
 use projectiles::ProjectilePlugin;
 use ship::ShipPlugin;
+use spatial::SpatialPlugin;
 use starfield::StarfieldPlugin;
 use enemies::EnemiesPlugin;
 use weapons::WeaponPlugin;
@@ -22,12 +37,19 @@ fn main() {
         .add_plugins(DefaultPlugins)
         .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
         //       .add_plugins(RapierDebugRenderPlugin::default())
+        .add_plugins(PoolPlugin)
+        .add_plugins(SpatialPlugin)
         .add_plugins(CollisionPlugin)
+        .add_plugins(FallbackPlugin)
         .add_plugins(HudPlugin)
+        .add_plugins(ParticlesPlugin)
+        .add_plugins(CameraShakePlugin)
+        .add_plugins(PlayAreaPlugin)
         .add_plugins(ShipPlugin)
         .add_plugins(WeaponPlugin)
         .add_plugins(ProjectilePlugin)
         .add_plugins(StarfieldPlugin)
+        .add_plugins(NebulaPlugin)
         .add_plugins(EnemiesPlugin)
         .add_systems(Startup, setup)
         .run();
@@ -44,9 +66,13 @@ fn setup(mut commands: Commands) {
         Transform::from_rotation(Quat::from_euler(EulerRot::XYZ, -0.5, -0.5, 0.0)),
     ));
 
-    // camera
+    // camera, shaken around its resting transform
+    let camera_transform = Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y);
     commands.spawn((
         Camera3d::default(),
-        Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+        camera_transform,
+        CameraRig {
+            base: camera_transform,
+        },
     ));
 }
@@ -0,0 +1,293 @@
+use crate::noise::value_noise_2d;
+use crate::starfield::{ScrollSpeed, StarfieldConfig};
+use bevy::asset::RenderAssetUsages;
+use bevy::color::Mix;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::tasks::{AsyncComputeTaskPool, Task, block_on, futures_lite::future};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Distance of the nebula behind the gameplay plane as a fraction of the starfield size,
+/// so it stays behind every star layer
+pub const NEBULA_DEPTH_FACTOR: f32 = 1.2;
+
+/// Half the size of the area dust drifts in, a bit larger than the view around the gameplay plane
+const DUST_HALF_WIDTH: f32 = 8.0;
+const DUST_HALF_HEIGHT: f32 = 5.0;
+
+/// Colors of a nebula, from its faint edges to its bright core
+#[derive(Clone, Copy, Debug)]
+pub struct NebulaPalette {
+    pub deep: Color,
+    pub mid: Color,
+    pub bright: Color,
+}
+
+impl NebulaPalette {
+    pub fn violet() -> Self {
+        Self {
+            deep: Color::srgb(0.08, 0.02, 0.2),
+            mid: Color::srgb(0.4, 0.1, 0.5),
+            bright: Color::srgb(0.9, 0.5, 0.8),
+        }
+    }
+
+    pub fn emerald() -> Self {
+        Self {
+            deep: Color::srgb(0.0, 0.08, 0.1),
+            mid: Color::srgb(0.05, 0.4, 0.35),
+            bright: Color::srgb(0.6, 0.95, 0.7),
+        }
+    }
+
+    pub fn crimson() -> Self {
+        Self {
+            deep: Color::srgb(0.15, 0.02, 0.02),
+            mid: Color::srgb(0.6, 0.1, 0.05),
+            bright: Color::srgb(1.0, 0.7, 0.4),
+        }
+    }
+
+    /// Palette of the given level, cycling through the presets
+    pub fn for_level(level: u32) -> Self {
+        match level % 3 {
+            0 => Self::violet(),
+            1 => Self::emerald(),
+            _ => Self::crimson(),
+        }
+    }
+}
+
+/// How the background is generated, change it (e.g. for a new level) to regenerate the background
+#[derive(Resource, Clone)]
+pub struct NebulaSettings {
+    pub seed: u32,
+    pub palette: NebulaPalette,
+    /// Size of the generated texture in pixels
+    pub resolution: UVec2,
+    /// Number of noise features across the texture, higher values give smaller clouds
+    pub frequency: f32,
+    /// Brightness of the nebula, kept low so it stays in the background
+    pub intensity: f32,
+    pub dust_count: usize,
+}
+
+impl Default for NebulaSettings {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            palette: NebulaPalette::for_level(0),
+            resolution: UVec2::new(512, 256),
+            frequency: 4.0,
+            intensity: 0.6,
+            dust_count: 150,
+        }
+    }
+}
+
+impl NebulaSettings {
+    /// Move on to the next level's background, with a new seed and the next palette
+    pub fn next_level(&mut self) {
+        self.seed = self.seed.wrapping_add(1);
+        self.palette = NebulaPalette::for_level(self.seed);
+    }
+}
+
+#[derive(Component)]
+pub struct Nebula;
+
+/// A mote of space dust drifting in front of the stars
+#[derive(Component)]
+pub struct Dust {
+    pub speed_factor: f32, // Fraction of the scroll speed, nearer motes drift faster
+}
+
+/// Fractal noise: octaves of value noise, each twice as detailed and half as strong
+fn fractal_noise(point: Vec2, seed: u32, octaves: u32) -> f32 {
+    let mut total = 0.0;
+    let mut amplitude = 0.5;
+    let mut frequency = 1.0;
+    let mut max_total = 0.0;
+    for octave in 0..octaves {
+        total += value_noise_2d(point * frequency, seed.wrapping_add(octave)) * amplitude;
+        max_total += amplitude;
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+    total / max_total
+}
+
+/// Generate the nebula texture on the CPU
+pub fn generate_nebula_image(settings: &NebulaSettings) -> Image {
+    let width = settings.resolution.x.max(1);
+    let height = settings.resolution.y.max(1);
+    let aspect_ratio = width as f32 / height as f32;
+    let palette = settings.palette;
+
+    let mut data = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let point = Vec2::new(
+                x as f32 / width as f32 * aspect_ratio,
+                y as f32 / height as f32,
+            ) * settings.frequency;
+
+            // Clouds where the noise is high, empty space elsewhere
+            let density = fractal_noise(point, settings.seed, 6);
+            let density = ((density - 0.4) / 0.4).clamp(0.0, 1.0);
+            // A second, larger noise picks the hue inside the clouds
+            let hue = fractal_noise(point * 0.5, settings.seed.wrapping_add(100), 3);
+
+            let color = palette
+                .deep
+                .mix(&palette.mid, hue)
+                .mix(&palette.bright, density * density);
+            let brightness = density * settings.intensity;
+            let [red, green, blue, _] = color.to_srgba().to_u8_array();
+            data.extend_from_slice(&[
+                (red as f32 * brightness) as u8,
+                (green as f32 * brightness) as u8,
+                (blue as f32 * brightness) as u8,
+                255,
+            ]);
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+/// The nebula texture being generated in the background
+#[derive(Resource, Default)]
+pub struct NebulaGeneration {
+    pub task: Option<Task<Image>>,
+}
+
+/// Start generating the nebula and respawn the dust, again whenever the settings change.
+/// The current nebula stays in place until the new one is ready.
+pub fn spawn_background(
+    mut commands: Commands,
+    settings: Res<NebulaSettings>,
+    mut generation: ResMut<NebulaGeneration>,
+    dust: Query<Entity, With<Dust>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    // Generating the texture takes a while, do it off the main thread.
+    // Replacing a task still running drops it, which cancels it.
+    let task_settings = settings.clone();
+    generation.task = Some(
+        AsyncComputeTaskPool::get().spawn(async move { generate_nebula_image(&task_settings) }),
+    );
+
+    for entity in dust.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    // Dust shares the nebula's brightest color, faintly
+    let dust_mesh = meshes.add(Sphere::new(0.015).mesh().uv(6, 4));
+    let dust_material = materials.add(StandardMaterial {
+        base_color: settings.palette.bright.with_alpha(0.35),
+        unlit: true,
+        alpha_mode: AlphaMode::Blend,
+        ..default()
+    });
+    // Seeded like the nebula, so a level always looks the same
+    let mut rng = StdRng::seed_from_u64(settings.seed as u64);
+    for _ in 0..settings.dust_count {
+        let position = Vec3::new(
+            rng.gen_range(-DUST_HALF_WIDTH..DUST_HALF_WIDTH),
+            rng.gen_range(-DUST_HALF_HEIGHT..DUST_HALF_HEIGHT),
+            rng.gen_range(-4.0..-0.5),
+        );
+        commands.spawn((
+            Dust {
+                speed_factor: rng.gen_range(1.2..2.0),
+            },
+            Mesh3d(dust_mesh.clone()),
+            MeshMaterial3d(dust_material.clone()),
+            Transform::from_translation(position),
+        ));
+    }
+}
+
+/// Swap the new nebula in once its texture is generated
+pub fn finish_nebula_generation(
+    mut commands: Commands,
+    mut generation: ResMut<NebulaGeneration>,
+    nebulas: Query<Entity, With<Nebula>>,
+    starfield_config: Res<StarfieldConfig>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Some(task) = generation.task.as_mut() else {
+        return;
+    };
+    let Some(image) = block_on(future::poll_once(task)) else {
+        return; // Still generating
+    };
+    generation.task = None;
+
+    for entity in nebulas.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    // A quad large enough to fill the view at its depth, even on wide windows
+    let depth = starfield_config.field_size * NEBULA_DEPTH_FACTOR;
+    let nebula_size = Vec2::new(2.7, 1.35) * depth;
+    commands.spawn((
+        Nebula,
+        Mesh3d(meshes.add(Rectangle::from_size(nebula_size))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color_texture: Some(images.add(image)),
+            unlit: true, // The nebula is not lit by the scene's light
+            ..default()
+        })),
+        Transform::from_xyz(0.0, 0.0, -depth),
+    ));
+}
+
+/// Drift dust from right to left, faster than the stars since it is nearer
+pub fn drift_dust(
+    mut dust: Query<(&mut Transform, &Dust)>,
+    scroll_speed: Res<ScrollSpeed>,
+    time: Res<Time>,
+) {
+    for (mut transform, mote) in dust.iter_mut() {
+        transform.translation.x -= scroll_speed.speed * mote.speed_factor * time.delta_secs();
+        if transform.translation.x < -DUST_HALF_WIDTH {
+            transform.translation.x += 2.0 * DUST_HALF_WIDTH;
+        }
+    }
+}
+
+pub struct NebulaPlugin;
+
+impl Plugin for NebulaPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NebulaSettings>()
+            .init_resource::<NebulaGeneration>()
+            .add_systems(
+                Update,
+                (
+                    (spawn_background, finish_nebula_generation).chain(),
+                    drift_dust,
+                ),
+            );
+    }
+}
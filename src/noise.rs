@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+
+/// Pseudo-random value between 0 and 1 for a lattice point
+pub fn lattice_hash(x: i32, y: i32, seed: u32) -> f32 {
+    let mut hash = (x as u32).wrapping_mul(374_761_393)
+        ^ (y as u32).wrapping_mul(668_265_263)
+        ^ seed.wrapping_mul(2_246_822_519);
+    hash = (hash ^ (hash >> 13)).wrapping_mul(1_274_126_177);
+    hash ^= hash >> 16;
+    hash as f32 / u32::MAX as f32
+}
+
+/// Smoothstep, so the noise has no visible creases along the lattice
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
+/// Smoothly interpolated value noise between 0 and 1, along a line
+pub fn value_noise_1d(x: f32, seed: u32) -> f32 {
+    let cell = x.floor();
+    let i = cell as i32;
+    lerp(
+        lattice_hash(i, 0, seed),
+        lattice_hash(i + 1, 0, seed),
+        smoothstep(x - cell),
+    )
+}
+
+/// Smoothly interpolated value noise between 0 and 1, over a plane
+pub fn value_noise_2d(point: Vec2, seed: u32) -> f32 {
+    let cell = point.floor();
+    let local = point - cell;
+    let (weight_x, weight_y) = (smoothstep(local.x), smoothstep(local.y));
+    let (x, y) = (cell.x as i32, cell.y as i32);
+
+    let bottom = lerp(
+        lattice_hash(x, y, seed),
+        lattice_hash(x + 1, y, seed),
+        weight_x,
+    );
+    let top = lerp(
+        lattice_hash(x, y + 1, seed),
+        lattice_hash(x + 1, y + 1, seed),
+        weight_x,
+    );
+    lerp(bottom, top, weight_y)
+}
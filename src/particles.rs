@@ -0,0 +1,480 @@
+use crate::collision::DamageDealt;
+use crate::pool::{EntityPool, PoolKind, Pooled, acquire_or_spawn, despawn_or_release};
+use bevy::color::Mix;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// Number of materials in each palette, particles step through them over their life
+pub const PALETTE_STEPS: usize = 8;
+
+/// Color gradients particles go through from birth to death
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ParticlePalette {
+    Fire,    // White hot to orange, red and dark smoke
+    Sparks,  // White to pale yellow
+    Debris,  // Gray hull fragments
+    Exhaust, // Pale blue engine flame
+}
+
+impl ParticlePalette {
+    pub const ALL: [ParticlePalette; 4] = [
+        ParticlePalette::Fire,
+        ParticlePalette::Sparks,
+        ParticlePalette::Debris,
+        ParticlePalette::Exhaust,
+    ];
+
+    /// Colors evenly spread over the life of a particle, the last one is reached when it dies
+    pub fn color_stops(&self) -> Vec<Color> {
+        match self {
+            ParticlePalette::Fire => vec![
+                Color::srgb(1.0, 1.0, 0.8),
+                Color::srgb(1.0, 0.7, 0.1),
+                Color::srgb(0.9, 0.2, 0.0),
+                Color::srgba(0.2, 0.1, 0.1, 0.0),
+            ],
+            ParticlePalette::Sparks => vec![
+                Color::WHITE,
+                Color::srgb(1.0, 0.9, 0.5),
+                Color::srgba(1.0, 0.6, 0.2, 0.0),
+            ],
+            ParticlePalette::Debris => vec![
+                Color::srgb(0.6, 0.6, 0.65),
+                Color::srgb(0.35, 0.35, 0.4),
+                Color::srgba(0.2, 0.2, 0.2, 0.0),
+            ],
+            ParticlePalette::Exhaust => vec![
+                Color::srgb(0.9, 0.95, 1.0),
+                Color::srgb(0.3, 0.6, 1.0),
+                Color::srgba(0.1, 0.2, 0.6, 0.0),
+            ],
+        }
+    }
+
+    /// Color of the palette at `t`, from 0 (birth) to 1 (death)
+    pub fn sample(&self, t: f32) -> Color {
+        let stops = self.color_stops();
+        let scaled = t.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+        let index = (scaled as usize).min(stops.len() - 2);
+        stops[index].mix(&stops[index + 1], scaled - index as f32)
+    }
+}
+
+/// Mesh and palette materials shared by every particle, so particles batch together
+#[derive(Resource)]
+pub struct ParticleAssets {
+    pub mesh: Handle<Mesh>,
+    pub palettes: HashMap<ParticlePalette, Vec<Handle<StandardMaterial>>>,
+}
+
+impl ParticleAssets {
+    /// Material of the palette for a particle at the given step of its life
+    pub fn material(&self, palette: ParticlePalette, step: usize) -> Handle<StandardMaterial> {
+        self.palettes[&palette][step.min(PALETTE_STEPS - 1)].clone()
+    }
+}
+
+/// How a burst of particles is emitted
+#[derive(Clone, Copy, Debug)]
+pub struct ParticleEmitter {
+    pub count: u32,
+    pub min_speed: f32,
+    pub max_speed: f32,
+    /// Main direction of the burst, zero to emit all around
+    pub direction: Vec3,
+    /// Maximum angle between a particle and the main direction, in radians
+    pub spread: f32,
+    pub min_lifetime: f32,
+    pub max_lifetime: f32,
+    pub start_size: f32,
+    pub end_size: f32,
+    /// Fraction of the speed lost per second
+    pub drag: f32,
+    pub palette: ParticlePalette,
+}
+
+impl ParticleEmitter {
+    /// A ball of fire growing and fading out
+    pub fn explosion() -> Self {
+        Self {
+            count: 24,
+            min_speed: 0.3,
+            max_speed: 1.5,
+            direction: Vec3::ZERO,
+            spread: 0.0,
+            min_lifetime: 0.3,
+            max_lifetime: 0.7,
+            start_size: 0.05,
+            end_size: 0.12,
+            drag: 2.5,
+            palette: ParticlePalette::Fire,
+        }
+    }
+
+    /// A few quick sparks where a hit landed
+    pub fn hit_sparks() -> Self {
+        Self {
+            count: 6,
+            min_speed: 1.0,
+            max_speed: 2.5,
+            direction: Vec3::ZERO,
+            spread: 0.0,
+            min_lifetime: 0.1,
+            max_lifetime: 0.25,
+            start_size: 0.02,
+            end_size: 0.0,
+            drag: 4.0,
+            palette: ParticlePalette::Sparks,
+        }
+    }
+
+    /// Slow fragments drifting away from a wreck
+    pub fn debris() -> Self {
+        Self {
+            count: 8,
+            min_speed: 0.2,
+            max_speed: 0.8,
+            direction: Vec3::ZERO,
+            spread: 0.0,
+            min_lifetime: 0.8,
+            max_lifetime: 1.5,
+            start_size: 0.04,
+            end_size: 0.02,
+            drag: 0.5,
+            palette: ParticlePalette::Debris,
+        }
+    }
+
+    /// Short-lived engine flame, for continuous trails
+    pub fn exhaust() -> Self {
+        Self {
+            count: 1,
+            min_speed: 0.5,
+            max_speed: 1.0,
+            direction: Vec3::ZERO,
+            spread: 0.25,
+            min_lifetime: 0.15,
+            max_lifetime: 0.3,
+            start_size: 0.03,
+            end_size: 0.005,
+            drag: 3.0,
+            palette: ParticlePalette::Exhaust,
+        }
+    }
+
+    /// Burning rocket fuel turning to smoke, for continuous trails
+    pub fn rocket_trail() -> Self {
+        Self {
+            count: 1,
+            min_speed: 0.2,
+            max_speed: 0.5,
+            direction: Vec3::ZERO,
+            spread: 0.3,
+            min_lifetime: 0.3,
+            max_lifetime: 0.5,
+            start_size: 0.015,
+            end_size: 0.04,
+            drag: 2.0,
+            palette: ParticlePalette::Fire,
+        }
+    }
+
+    /// Emit along a direction instead of all around
+    pub fn with_direction(mut self, direction: Vec3, spread: f32) -> Self {
+        self.direction = direction;
+        self.spread = spread;
+        self
+    }
+
+    /// Scale the number of particles and their size, for bigger or smaller explosions
+    pub fn scaled(mut self, factor: f32) -> Self {
+        self.count = ((self.count as f32 * factor).round() as u32).max(1);
+        self.start_size *= factor.sqrt();
+        self.end_size *= factor.sqrt();
+        self
+    }
+
+    /// Random velocity of a new particle
+    fn random_velocity(&self, rng: &mut impl Rng) -> Vec3 {
+        let direction = match self.direction.try_normalize() {
+            Some(direction) => {
+                // Tilt the main direction by up to the spread angle, around a random axis
+                let axis =
+                    Quat::from_axis_angle(direction, rng.gen_range(0.0..std::f32::consts::TAU))
+                        * direction.any_orthonormal_vector();
+                Quat::from_axis_angle(axis, rng.gen_range(0.0..=self.spread)) * direction
+            }
+            None => Vec3::new(
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+            )
+            .normalize_or(Vec3::X),
+        };
+        direction * rng.gen_range(self.min_speed..=self.max_speed)
+    }
+}
+
+/// Ask for a burst of particles
+#[derive(Message, Clone, Copy, Debug)]
+pub struct ParticleBurst {
+    pub position: Vec3,
+    /// Velocity added to every particle, e.g. the velocity of what exploded
+    pub velocity: Vec3,
+    pub emitter: ParticleEmitter,
+}
+
+impl ParticleBurst {
+    pub fn new(position: Vec3, emitter: ParticleEmitter) -> Self {
+        Self {
+            position,
+            velocity: Vec3::ZERO,
+            emitter,
+        }
+    }
+}
+
+/// Emits particles continuously while active, e.g. engine exhaust.
+/// Whatever owns the emitter turns it on and off and points it every frame.
+#[derive(Component, Clone)]
+pub struct TrailEmitter {
+    pub emitter: ParticleEmitter,
+    /// Particles emitted per second
+    pub rate: f32,
+    /// Where particles are emitted, in world units from the entity
+    pub offset: Vec3,
+    /// Direction particles are emitted in, the emitter's spread applies around it
+    pub direction: Vec3,
+    pub active: bool,
+    /// Burst emitted once when the emitter turns on, to show ignition
+    pub ignition: Option<ParticleEmitter>,
+    /// Whether the emitter was already active last frame
+    pub ignited: bool,
+    /// Fraction of a particle carried over to the next frame
+    pub pending: f32,
+}
+
+impl TrailEmitter {
+    pub fn new(emitter: ParticleEmitter, rate: f32) -> Self {
+        Self {
+            emitter,
+            rate,
+            offset: Vec3::ZERO,
+            direction: Vec3::NEG_X,
+            active: false,
+            ignition: None,
+            ignited: false,
+            pending: 0.0,
+        }
+    }
+
+    pub fn with_ignition(mut self, ignition: ParticleEmitter) -> Self {
+        self.ignition = Some(ignition);
+        self
+    }
+}
+
+/// A pooled particle moving in a straight line, shrinking or growing and changing color
+#[derive(Component, Clone)]
+pub struct Particle {
+    pub velocity: Vec3,
+    pub age: f32,
+    pub lifetime: f32,
+    pub start_size: f32,
+    pub end_size: f32,
+    pub drag: f32,
+    pub palette: ParticlePalette,
+    /// Palette step of the current material
+    pub step: usize,
+}
+
+pub fn setup_particle_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let palettes = ParticlePalette::ALL
+        .iter()
+        .map(|palette| {
+            let steps = (0..PALETTE_STEPS)
+                .map(|step| {
+                    materials.add(StandardMaterial {
+                        base_color: palette.sample(step as f32 / (PALETTE_STEPS - 1) as f32),
+                        unlit: true, // Particles glow on their own
+                        alpha_mode: AlphaMode::Blend,
+                        ..default()
+                    })
+                })
+                .collect();
+            (*palette, steps)
+        })
+        .collect();
+
+    commands.insert_resource(ParticleAssets {
+        mesh: meshes.add(Sphere::new(1.0).mesh().uv(6, 4)), // Scaled to the particle size
+        palettes,
+    });
+}
+
+/// Spawn the particles of every requested burst, reusing released particles first
+pub fn spawn_particle_bursts(
+    mut commands: Commands,
+    mut bursts: MessageReader<ParticleBurst>,
+    particle_assets: Res<ParticleAssets>,
+    mut pool: ResMut<EntityPool>,
+) {
+    let mut rng = rand::thread_rng();
+
+    for burst in bursts.read() {
+        let emitter = &burst.emitter;
+        for _ in 0..emitter.count {
+            let particle = Particle {
+                velocity: burst.velocity + emitter.random_velocity(&mut rng),
+                age: 0.0,
+                lifetime: rng.gen_range(emitter.min_lifetime..=emitter.max_lifetime),
+                start_size: emitter.start_size,
+                end_size: emitter.end_size,
+                drag: emitter.drag,
+                palette: emitter.palette,
+                step: 0,
+            };
+            let (entity, fresh) = acquire_or_spawn(
+                &mut commands,
+                &mut pool,
+                PoolKind::Particle,
+                (
+                    particle,
+                    Transform::from_translation(burst.position)
+                        .with_scale(Vec3::splat(emitter.start_size)),
+                    MeshMaterial3d(particle_assets.material(emitter.palette, 0)),
+                ),
+            );
+            if fresh {
+                commands
+                    .entity(entity)
+                    .insert(Mesh3d(particle_assets.mesh.clone()));
+            }
+        }
+    }
+}
+
+/// Move, resize and recolor particles, releasing them at the end of their life
+pub fn update_particles(
+    mut commands: Commands,
+    mut particles: Query<(
+        Entity,
+        &mut Particle,
+        &mut Transform,
+        &mut MeshMaterial3d<StandardMaterial>,
+        Option<&Pooled>,
+    )>,
+    particle_assets: Res<ParticleAssets>,
+    mut pool: ResMut<EntityPool>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_secs();
+
+    for (entity, mut particle, mut transform, mut material, pooled) in particles.iter_mut() {
+        particle.age += delta;
+        if particle.age >= particle.lifetime {
+            despawn_or_release(&mut commands, &mut pool, entity, pooled);
+            continue;
+        }
+
+        let damping = (1.0 - particle.drag * delta).max(0.0);
+        particle.velocity *= damping;
+        transform.translation += particle.velocity * delta;
+
+        let life = particle.age / particle.lifetime;
+        transform.scale =
+            Vec3::splat(particle.start_size + (particle.end_size - particle.start_size) * life);
+
+        // Only swap the material when the particle reaches the next palette step
+        let step = (life * PALETTE_STEPS as f32) as usize;
+        if step != particle.step {
+            particle.step = step;
+            material.0 = particle_assets.material(particle.palette, step);
+        }
+    }
+}
+
+/// Emit particles from active trail emitters, and an ignition burst when they turn on
+pub fn emit_trails(
+    mut trails: Query<(&mut TrailEmitter, &GlobalTransform, Option<&Velocity>)>,
+    mut bursts: MessageWriter<ParticleBurst>,
+    time: Res<Time>,
+) {
+    let inherited_velocity = 0.3; // Fraction of the emitter's velocity kept by its particles
+
+    for (mut trail, transform, velocity) in trails.iter_mut() {
+        if !trail.active {
+            trail.ignited = false;
+            trail.pending = 0.0;
+            continue;
+        }
+
+        let position = transform.translation() + trail.offset;
+        let velocity = velocity.map_or(Vec3::ZERO, |velocity| velocity.linvel) * inherited_velocity;
+
+        if !trail.ignited {
+            trail.ignited = true;
+            if let Some(ignition) = trail.ignition {
+                bursts.write(ParticleBurst {
+                    position,
+                    velocity,
+                    emitter: ignition,
+                });
+            }
+        }
+
+        trail.pending += trail.rate * time.delta_secs();
+        let count = trail.pending.floor();
+        if count < 1.0 {
+            continue;
+        }
+        trail.pending -= count;
+
+        let mut emitter = trail
+            .emitter
+            .with_direction(trail.direction, trail.emitter.spread);
+        emitter.count = count as u32;
+        bursts.write(ParticleBurst {
+            position,
+            velocity,
+            emitter,
+        });
+    }
+}
+
+/// Throw sparks wherever something takes damage
+pub fn spark_on_damage(
+    mut damage_dealt: MessageReader<DamageDealt>,
+    mut bursts: MessageWriter<ParticleBurst>,
+) {
+    for damage in damage_dealt.read() {
+        bursts.write(ParticleBurst::new(
+            damage.position,
+            ParticleEmitter::hit_sparks(),
+        ));
+    }
+}
+
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<ParticleBurst>()
+            .add_systems(Startup, setup_particle_assets)
+            .add_systems(
+                Update,
+                (
+                    spark_on_damage.before(spawn_particle_bursts),
+                    spawn_particle_bursts,
+                    update_particles,
+                    emit_trails,
+                ),
+            );
+    }
+}
@@ -0,0 +1,126 @@
+use crate::camera_shake::CameraRig;
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowResized};
+use rand::Rng;
+
+/// The part of the z = 0 gameplay plane visible through the camera.
+/// Recomputed from the camera projection whenever the window is resized.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct PlayArea {
+    pub center: Vec2,
+    pub half_size: Vec2,
+}
+
+impl Default for PlayArea {
+    fn default() -> Self {
+        // What the default camera sees in a 1280x720 window
+        Self {
+            center: Vec2::ZERO,
+            half_size: Vec2::new(3.68, 2.07),
+        }
+    }
+}
+
+impl PlayArea {
+    pub fn left(&self) -> f32 {
+        self.center.x - self.half_size.x
+    }
+
+    pub fn right(&self) -> f32 {
+        self.center.x + self.half_size.x
+    }
+
+    pub fn bottom(&self) -> f32 {
+        self.center.y - self.half_size.y
+    }
+
+    pub fn top(&self) -> f32 {
+        self.center.y + self.half_size.y
+    }
+
+    /// Whether the position is inside the area grown by `margin` (shrunk when negative)
+    pub fn contains(&self, position: Vec3, margin: f32) -> bool {
+        (position.x - self.center.x).abs() <= self.half_size.x + margin
+            && (position.y - self.center.y).abs() <= self.half_size.y + margin
+    }
+
+    /// Clamp the position into the area shrunk by `inset`
+    pub fn clamp(&self, position: Vec3, inset: f32) -> Vec3 {
+        let half_size = (self.half_size - Vec2::splat(inset)).max(Vec2::ZERO);
+        let clamped = position
+            .truncate()
+            .clamp(self.center - half_size, self.center + half_size);
+        clamped.extend(position.z)
+    }
+
+    /// Zero the velocity components taking a position past the edges of the area shrunk by `inset`
+    pub fn stop_at_edges(&self, position: Vec3, velocity: Vec3, inset: f32) -> Vec3 {
+        let mut velocity = velocity;
+        if (position.x > self.right() - inset && velocity.x > 0.0)
+            || (position.x < self.left() + inset && velocity.x < 0.0)
+        {
+            velocity.x = 0.0;
+        }
+        if (position.y > self.top() - inset && velocity.y > 0.0)
+            || (position.y < self.bottom() + inset && velocity.y < 0.0)
+        {
+            velocity.y = 0.0;
+        }
+        velocity
+    }
+
+    /// A random position just off the right edge, where new enemies enter from
+    pub fn spawn_position(&self, margin: f32) -> Vec3 {
+        let mut rng = rand::thread_rng();
+        let spawn_half_height = (self.half_size.y - 0.1).max(0.0); // Keep enemies fully on screen vertically
+        let y = self.center.y + rng.gen_range(-spawn_half_height..=spawn_half_height);
+        Vec3::new(self.right() + margin, y, 0.0)
+    }
+}
+
+/// Recompute the play area from the camera's field of view and distance to the gameplay plane
+pub fn update_play_area(
+    mut resized: MessageReader<WindowResized>,
+    cameras: Query<(Ref<Projection>, &CameraRig)>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut play_area: ResMut<PlayArea>,
+) {
+    let window_resized = resized.read().count() > 0;
+    let Ok((projection, rig)) = cameras.single() else {
+        return;
+    };
+    // The projection also changes when the camera is spawned
+    if !window_resized && !projection.is_changed() {
+        return;
+    }
+    let Ok(window) = windows.single() else {
+        return;
+    };
+    let Projection::Perspective(perspective) = projection.as_ref() else {
+        return;
+    };
+    if window.height() <= 0.0 {
+        return; // Minimized window
+    }
+
+    // Use the resting camera transform, so screen shake does not move the play area
+    let distance = rig.base.translation.z.abs();
+    let half_height = distance * (perspective.fov / 2.0).tan();
+    let aspect_ratio = window.width() / window.height();
+
+    *play_area = PlayArea {
+        center: rig.base.translation.truncate(),
+        half_size: Vec2::new(half_height * aspect_ratio, half_height),
+    };
+}
+
+pub struct PlayAreaPlugin;
+
+impl Plugin for PlayAreaPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlayArea>()
+            // Once the camera is spawned, so startup systems can use the play area too
+            .add_systems(PostStartup, update_play_area)
+            .add_systems(PreUpdate, update_play_area);
+    }
+}
@@ -0,0 +1,270 @@
+use crate::collision::Collidable;
+use crate::enemies::Enemy;
+use crate::enemies::formation::{Formation, FormationSlot};
+use crate::enemies::kamikaze::Kamikaze;
+use crate::particles::{Particle, TrailEmitter};
+use crate::projectiles::{Piercing, Projectile, Ricochet};
+use crate::weapons::weapon::Weapon;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// The kinds of pooled entities, each kind has its own free list
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PoolKind {
+    CannonBall,
+    Rocket,
+    Drone,
+    Kamikaze,
+    Turret,
+    Asteroid,
+    Particle,
+}
+
+/// A marker component for entities recycled through the EntityPool instead of being despawned
+#[derive(Component, Clone, Copy)]
+pub struct Pooled(pub PoolKind);
+
+/// Free lists of hidden entities waiting to be reused.
+/// Entities released during a frame only become available at the end of it, so an entity is never
+/// handed out again before the commands hiding it have run.
+#[derive(Resource, Default)]
+pub struct EntityPool {
+    free: HashMap<PoolKind, Vec<Entity>>,
+    /// Entities released this frame, moved to the free lists by recycle_released_entities
+    pending: Vec<(PoolKind, Entity)>,
+    /// Every entity released and not reused yet, and entities despawned this frame
+    released: HashSet<Entity>,
+    despawned: Vec<Entity>,
+}
+
+impl EntityPool {
+    /// Take a released entity of the given kind, if any
+    pub fn acquire(&mut self, kind: PoolKind) -> Option<Entity> {
+        let entity = self
+            .free
+            .get_mut(&kind)
+            .and_then(|entities| entities.pop())?;
+        self.released.remove(&entity);
+        Some(entity)
+    }
+
+    /// Give an entity back to the pool. Returns false if it was already released, for example
+    /// by another system in the same frame.
+    pub fn push(&mut self, kind: PoolKind, entity: Entity) -> bool {
+        if !self.released.insert(entity) {
+            return false;
+        }
+        self.pending.push((kind, entity));
+        true
+    }
+
+    /// Record a non-pooled entity as despawned for the rest of the frame.
+    /// Returns false if it was already despawned.
+    pub fn mark_despawned(&mut self, entity: Entity) -> bool {
+        if !self.released.insert(entity) {
+            return false;
+        }
+        self.despawned.push(entity);
+        true
+    }
+
+    /// Whether the entity was released or despawned and must not be processed any more
+    pub fn is_released(&self, entity: Entity) -> bool {
+        self.released.contains(&entity)
+    }
+
+    /// Make the entities released this frame available, and forget the despawned ones
+    pub fn recycle(&mut self) {
+        for (kind, entity) in self.pending.drain(..) {
+            self.free.entry(kind).or_default().push(entity);
+        }
+        for entity in self.despawned.drain(..) {
+            self.released.remove(&entity);
+        }
+    }
+}
+
+/// Reuse a released entity of the given kind, or spawn a new one.
+/// The bundle must reset every gameplay component of the entity.
+/// Returns the entity and whether it was freshly spawned: only fresh entities need their
+/// one-time components (meshes, scenes, colliders, children).
+pub fn acquire_or_spawn(
+    commands: &mut Commands,
+    pool: &mut ResMut<EntityPool>,
+    kind: PoolKind,
+    bundle: impl Bundle,
+) -> (Entity, bool) {
+    if let Some(entity) = pool.acquire(kind) {
+        commands
+            .entity(entity)
+            .remove::<RigidBodyDisabled>()
+            .insert((bundle, Visibility::Inherited));
+        (entity, false)
+    } else {
+        (commands.spawn((bundle, Pooled(kind))).id(), true)
+    }
+}
+
+/// Hide a pooled entity and strip its gameplay components until it is reused,
+/// other entities are simply despawned. Entities already released this frame are left alone.
+pub fn despawn_or_release(
+    commands: &mut Commands,
+    pool: &mut ResMut<EntityPool>,
+    entity: Entity,
+    pooled: Option<&Pooled>,
+) {
+    if let Some(Pooled(kind)) = pooled {
+        if !pool.push(*kind, entity) {
+            return;
+        }
+        commands
+            .entity(entity)
+            .remove::<(
+                Projectile,
+                Collidable,
+                Enemy,
+                Weapon,
+                Piercing,
+                Ricochet,
+                Kamikaze,
+                Formation,
+                FormationSlot,
+                Particle,
+                TrailEmitter,
+            )>()
+            .insert((Velocity::zero(), RigidBodyDisabled, Visibility::Hidden));
+    } else if pool.mark_despawned(entity) {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Hand the entities released this frame back out from the next frame on
+pub fn recycle_released_entities(mut pool: ResMut<EntityPool>) {
+    pool.recycle();
+}
+
+pub struct PoolPlugin;
+
+impl Plugin for PoolPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EntityPool>()
+            .add_systems(Last, recycle_released_entities);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use std::time::{Duration, Instant};
+
+    const ENTITIES_PER_FRAME: usize = 1000;
+    const FRAMES: usize = 50;
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.init_resource::<EntityPool>();
+        world
+    }
+
+    fn live_entities(world: &mut World) -> usize {
+        world
+            .query_filtered::<(), (With<Velocity>, Without<RigidBodyDisabled>)>()
+            .iter(world)
+            .count()
+    }
+
+    /// Spawn a batch of entities then remove them again every frame, through the pool or not
+    fn churn(world: &mut World, use_pool: bool) -> Duration {
+        let start = Instant::now();
+        for _ in 0..FRAMES {
+            world
+                .run_system_once(
+                    move |mut commands: Commands, mut pool: ResMut<EntityPool>| {
+                        for _ in 0..ENTITIES_PER_FRAME {
+                            let bundle = (Transform::default(), Velocity::zero());
+                            if use_pool {
+                                acquire_or_spawn(
+                                    &mut commands,
+                                    &mut pool,
+                                    PoolKind::Particle,
+                                    bundle,
+                                );
+                            } else {
+                                commands.spawn(bundle);
+                            }
+                        }
+                    },
+                )
+                .unwrap();
+            world
+                .run_system_once(
+                    |mut commands: Commands,
+                     mut pool: ResMut<EntityPool>,
+                     entities: Query<
+                        (Entity, Option<&Pooled>),
+                        (With<Velocity>, Without<RigidBodyDisabled>),
+                    >| {
+                        for (entity, pooled) in entities.iter() {
+                            despawn_or_release(&mut commands, &mut pool, entity, pooled);
+                        }
+                    },
+                )
+                .unwrap();
+            world.run_system_once(recycle_released_entities).unwrap();
+        }
+        start.elapsed()
+    }
+
+    #[test]
+    fn pooled_entities_are_reused_every_frame() {
+        let mut world = world();
+        churn(&mut world, true);
+        assert_eq!(live_entities(&mut world), 0);
+        // Every frame after the first reuses the entities released before
+        let pooled_count = world.query::<&Pooled>().iter(&world).count();
+        assert_eq!(pooled_count, ENTITIES_PER_FRAME);
+    }
+
+    /// Timing comparison, see the README for how to run it
+    #[test]
+    #[ignore]
+    fn pooling_timing() {
+        let spawned = churn(&mut world(), false);
+        let pooled = churn(&mut world(), true);
+        println!(
+            "{} frames of {} entities: spawn/despawn {:?}, acquire_or_spawn/despawn_or_release {:?}",
+            FRAMES, ENTITIES_PER_FRAME, spawned, pooled
+        );
+    }
+
+    #[test]
+    fn released_entities_wait_for_the_end_of_the_frame() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let mut pool = EntityPool::default();
+
+        assert!(pool.push(PoolKind::Drone, entity));
+        assert!(!pool.push(PoolKind::Drone, entity));
+        assert!(pool.is_released(entity));
+        assert_eq!(pool.acquire(PoolKind::Drone), None);
+
+        pool.recycle();
+        assert_eq!(pool.acquire(PoolKind::Drone), Some(entity));
+        assert!(!pool.is_released(entity));
+        assert_eq!(pool.acquire(PoolKind::Drone), None);
+    }
+
+    #[test]
+    fn despawned_entities_are_forgotten_at_the_end_of_the_frame() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let mut pool = EntityPool::default();
+
+        assert!(pool.mark_despawned(entity));
+        assert!(!pool.mark_despawned(entity));
+        pool.recycle();
+        assert!(!pool.is_released(entity));
+    }
+}
@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+
+/// Mesh, material and scene handles shared by every projectile of the same kind
+#[derive(Resource)]
+pub struct ProjectileAssets {
+    pub cannon_ball_mesh: Handle<Mesh>,
+    pub cannon_ball_material: Handle<StandardMaterial>,
+    pub rocket_scene: Handle<Scene>,
+}
+
+/// Builds the projectile assets once, so spawners never add a new asset per shot
+pub fn setup_projectile_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.insert_resource(ProjectileAssets {
+        cannon_ball_mesh: meshes.add(Sphere::new(0.03)),
+        cannon_ball_material: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.0, 1.0, 1.0), // Cyan projectile
+            emissive: Color::srgb(1.0, 1.0, 0.0).into(),
+            ..default()
+        }),
+        rocket_scene: asset_server.load("models/projectiles/rocket.glb#Scene0"),
+    });
+}
@@ -1,51 +1,99 @@
 use crate::collision::{Collidable, Team};
-use crate::projectiles::Projectile;
+use crate::pool::{EntityPool, PoolKind, acquire_or_spawn};
+use crate::projectiles::{Projectile, ProjectileAssets, ProjectileExpiry};
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
-/// Spawns a cannon ball projectile using mesh information from this module
+/// Spawns a cannon ball projectile using the shared cannon ball mesh and material
 pub fn spawn_cannon_ball_projectile(
     commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &mut ResMut<Assets<StandardMaterial>>,
+    projectile_assets: &Res<ProjectileAssets>,
+    pool: &mut ResMut<EntityPool>,
     _asset_server: &Res<AssetServer>,
     _scene_spawner: &mut ResMut<SceneSpawner>,
     position: Vec3,
     velocity: Vec3,
     rotation: Quat,
     team: Team,
-) {
-    let projectile_mesh = meshes.add(Sphere::new(0.03));
-    let projectile_material = materials.add(StandardMaterial {
-        base_color: Color::srgb(0.0, 1.0, 1.0), // Cyan projectile
-        emissive: Color::srgb(1.0, 1.0, 0.0).into(),
-        ..default()
-    });
-
+) -> Entity {
     // Calculate forward direction from rotation
     let forward_direction = rotation * Vec3::Z;
 
-    commands.spawn((
-        Projectile {
-            acceleration: 0.0,
-            agility: 0.0,
-            direction: forward_direction.normalize(),
-            homing: false,
-            activation_timer: 0.0,
-            enemy: None,
-            mesh_rotation_offset: Quat::IDENTITY, // No mesh offset for cannon balls
-        },
-        Collidable::new(10.0, 1.0, team), // 10 damage, 1 HP, use passed team
-        Velocity::linear(velocity),
-        RigidBody::KinematicVelocityBased,
-        Collider::ball(0.03),
-        ActiveEvents::COLLISION_EVENTS,
-        Mesh3d(projectile_mesh),
-        MeshMaterial3d(projectile_material),
-        Transform {
-            translation: position,
-            rotation,
-            scale: Vec3::ONE,
+    let (projectile_entity, fresh) = acquire_or_spawn(
+        commands,
+        pool,
+        PoolKind::CannonBall,
+        (
+            Projectile {
+                acceleration: 0.0,
+                agility: 0.0,
+                direction: forward_direction.normalize(),
+                homing: false,
+                activation_timer: 0.0,
+                enemy: None,
+                mesh_rotation_offset: Quat::IDENTITY, // No mesh offset for cannon balls
+                max_distance: Some(15.0),             // Cannon balls fizzle out after 15 units
+                ..default()
+            },
+            Collidable::new(10.0, 1.0, team), // 10 damage, 1 HP, use passed team
+            Velocity::linear(velocity),
+            Transform {
+                translation: position,
+                rotation,
+                scale: Vec3::ONE,
+            },
+        ),
+    );
+
+    // Recycled cannon balls already have their mesh and collider
+    if fresh {
+        commands.entity(projectile_entity).insert((
+            RigidBody::KinematicVelocityBased,
+            Collider::ball(0.03),
+            ActiveEvents::COLLISION_EVENTS,
+            Mesh3d(projectile_assets.cannon_ball_mesh.clone()),
+            MeshMaterial3d(projectile_assets.cannon_ball_material.clone()),
+        ));
+    }
+
+    projectile_entity
+}
+
+/// Spawns a flak shell: a cannon ball bursting into a fan of cannon balls after a short distance
+pub fn spawn_flak_projectile(
+    commands: &mut Commands,
+    projectile_assets: &Res<ProjectileAssets>,
+    pool: &mut ResMut<EntityPool>,
+    asset_server: &Res<AssetServer>,
+    scene_spawner: &mut ResMut<SceneSpawner>,
+    position: Vec3,
+    velocity: Vec3,
+    rotation: Quat,
+    team: Team,
+) -> Entity {
+    let projectile_entity = spawn_cannon_ball_projectile(
+        commands,
+        projectile_assets,
+        pool,
+        asset_server,
+        scene_spawner,
+        position,
+        velocity,
+        rotation,
+        team,
+    );
+
+    // Override the cannon ball behaviour to split after 3 units
+    commands.entity(projectile_entity).insert(Projectile {
+        direction: (rotation * Vec3::Z).normalize(),
+        max_distance: Some(3.0),
+        expiry: ProjectileExpiry::Split {
+            count: 5,
+            spread: std::f32::consts::FRAC_PI_2,
+            spawner: spawn_cannon_ball_projectile,
         },
-    ));
+        ..default()
+    });
+
+    projectile_entity
 }
@@ -0,0 +1,275 @@
+use bevy::prelude::*;
+
+/// Below this length, vectors are considered null and ignored by the guidance maths
+const EPSILON: f32 = 1e-6;
+
+/// How a homing projectile chooses the direction to turn toward
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GuidanceMode {
+    /// Always turn toward the target's current position
+    PurePursuit,
+    /// Turn toward the point where the projectile can intercept the target
+    #[allow(dead_code)] // Not used by any weapon yet
+    LeadPursuit,
+    /// Turn proportionally to the rotation rate of the line of sight to the target.
+    /// The gain is usually between 3 and 5.
+    ProportionalNavigation { gain: f32 },
+}
+
+/// Rotates a unit direction toward another one by at most `max_angle` radians.
+/// This is a real spherical rotation: the result always stays a unit vector and never contains NaN,
+/// even when both directions are opposite.
+pub fn rotate_toward(current: Vec3, desired: Vec3, max_angle: f32) -> Vec3 {
+    let current = current.normalize_or(Vec3::X);
+    let Some(desired) = desired.try_normalize() else {
+        return current;
+    };
+
+    // Clamp the dot product, rounding errors can push it slightly outside of acos' domain
+    let angle = current.dot(desired).clamp(-1.0, 1.0).acos();
+    if angle <= max_angle.max(0.0) {
+        return desired;
+    }
+
+    let axis = current.cross(desired).try_normalize().unwrap_or_else(|| {
+        // Opposite directions have no rotation axis, prefer turning within the XY play plane
+        Vec3::Z
+            .reject_from_normalized(current)
+            .try_normalize()
+            .unwrap_or_else(|| current.any_orthonormal_vector())
+    });
+
+    (Quat::from_axis_angle(axis, max_angle) * current).normalize_or(current)
+}
+
+/// Returns the point where a projectile flying at `speed` can intercept a target moving at
+/// constant velocity, or the target's current position if no interception is possible.
+pub fn intercept_point(
+    projectile_position: Vec3,
+    speed: f32,
+    target_position: Vec3,
+    target_velocity: Vec3,
+) -> Vec3 {
+    let to_target = target_position - projectile_position;
+
+    // Solve |to_target + target_velocity * t| = speed * t for the smallest positive t
+    let a = target_velocity.length_squared() - speed * speed;
+    let b = 2.0 * to_target.dot(target_velocity);
+    let c = to_target.length_squared();
+
+    let time = if a.abs() < EPSILON {
+        // Target as fast as the projectile, the equation is linear
+        if b.abs() < EPSILON {
+            None
+        } else {
+            Some(-c / b)
+        }
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            None
+        } else {
+            let root = discriminant.sqrt();
+            let t1 = (-b - root) / (2.0 * a);
+            let t2 = (-b + root) / (2.0 * a);
+            [t1, t2].into_iter().filter(|t| *t > 0.0).reduce(f32::min)
+        }
+    };
+
+    match time {
+        Some(time) if time.is_finite() && time > 0.0 => target_position + target_velocity * time,
+        _ => target_position,
+    }
+}
+
+/// Returns the direction a homing projectile wants to fly toward this frame, before the turn
+/// rate limit is applied.
+pub fn desired_direction(
+    mode: GuidanceMode,
+    current_direction: Vec3,
+    projectile_position: Vec3,
+    projectile_velocity: Vec3,
+    target_position: Vec3,
+    target_velocity: Vec3,
+    delta_secs: f32,
+) -> Vec3 {
+    let line_of_sight = target_position - projectile_position;
+    if line_of_sight.length_squared() < EPSILON {
+        return current_direction;
+    }
+
+    match mode {
+        GuidanceMode::PurePursuit => line_of_sight.normalize(),
+        GuidanceMode::LeadPursuit => {
+            // Aim assuming the projectile keeps at least its current speed
+            let speed = projectile_velocity.length();
+            let aim_point =
+                intercept_point(projectile_position, speed, target_position, target_velocity);
+            (aim_point - projectile_position).normalize_or(line_of_sight.normalize())
+        }
+        GuidanceMode::ProportionalNavigation { gain } => {
+            // Proportional navigation only corrects small deviations, when the target is behind
+            // the projectile fall back to pursuit to turn around first
+            if current_direction.dot(line_of_sight) <= 0.0 {
+                return line_of_sight.normalize();
+            }
+
+            // Rotation rate of the line of sight, as a rotation vector
+            let relative_velocity = target_velocity - projectile_velocity;
+            let line_of_sight_rate =
+                line_of_sight.cross(relative_velocity) / line_of_sight.length_squared();
+            let rate = line_of_sight_rate.length();
+            if !rate.is_finite() || rate < EPSILON {
+                return current_direction;
+            }
+
+            let turn = Quat::from_axis_angle(line_of_sight_rate / rate, gain * rate * delta_secs);
+            (turn * current_direction).normalize_or(current_direction)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODES: [GuidanceMode; 3] = [
+        GuidanceMode::PurePursuit,
+        GuidanceMode::LeadPursuit,
+        GuidanceMode::ProportionalNavigation { gain: 4.0 },
+    ];
+
+    fn assert_finite(vector: Vec3) {
+        assert!(vector.is_finite(), "{vector:?} is not finite");
+    }
+
+    #[test]
+    fn rotate_toward_respects_the_turn_rate() {
+        let max_turn_rate = 3.0;
+        let delta_secs = 1.0 / 60.0;
+        let max_angle = max_turn_rate * delta_secs;
+        let directions = [
+            Vec3::X,
+            Vec3::NEG_X,
+            Vec3::Y,
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(-1.0, 0.001, 0.0),
+            Vec3::new(0.3, -0.7, 0.2),
+        ];
+
+        for current in directions {
+            for desired in directions {
+                let current = current.normalize();
+                let rotated = rotate_toward(current, desired, max_angle);
+                assert_finite(rotated);
+                assert!((rotated.length() - 1.0).abs() < 1e-4);
+                assert!(
+                    current.angle_between(rotated) <= max_angle + 1e-4,
+                    "turned from {current:?} to {rotated:?} toward {desired:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rotate_toward_reaches_close_directions() {
+        let desired = Vec3::new(1.0, 0.01, 0.0).normalize();
+        let rotated = rotate_toward(Vec3::X, desired, 0.1);
+        assert!(rotated.distance(desired) < 1e-5);
+    }
+
+    #[test]
+    fn rotate_toward_ignores_null_vectors() {
+        assert_finite(rotate_toward(Vec3::ZERO, Vec3::Y, 0.1));
+        assert_eq!(rotate_toward(Vec3::X, Vec3::ZERO, 0.1), Vec3::X);
+    }
+
+    #[test]
+    fn intercept_point_is_finite_when_unreachable() {
+        // Target flying away faster than the projectile
+        let point = intercept_point(Vec3::ZERO, 1.0, Vec3::X, Vec3::new(5.0, 0.0, 0.0));
+        assert_eq!(point, Vec3::X);
+    }
+
+    #[test]
+    fn intercept_point_is_finite_without_relative_motion() {
+        let point = intercept_point(Vec3::ZERO, 0.0, Vec3::new(2.0, 1.0, 0.0), Vec3::ZERO);
+        assert_eq!(point, Vec3::new(2.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn intercept_point_is_finite_on_top_of_the_target() {
+        let position = Vec3::new(1.0, 2.0, 0.0);
+        for speed in [0.0, 1.0, 3.0] {
+            let point = intercept_point(position, speed, position, Vec3::new(1.0, 0.0, 0.0));
+            assert_finite(point);
+        }
+    }
+
+    #[test]
+    fn intercept_point_leads_a_moving_target() {
+        // Target crossing at speed 1, projectile at speed 2 from 4 units away
+        let point = intercept_point(
+            Vec3::ZERO,
+            2.0,
+            Vec3::new(4.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+        );
+        let time = point.length() / 2.0;
+        assert!(point.distance(Vec3::new(4.0, time, 0.0)) < 1e-4);
+    }
+
+    #[test]
+    fn desired_direction_is_finite_in_degenerate_cases() {
+        let delta_secs = 1.0 / 60.0;
+        let cases = [
+            // Target on top of the projectile
+            (Vec3::ZERO, Vec3::X, Vec3::ZERO, Vec3::Y),
+            // No relative motion
+            (Vec3::ZERO, Vec3::X, Vec3::new(3.0, 1.0, 0.0), Vec3::X),
+            // Unreachable target
+            (
+                Vec3::ZERO,
+                Vec3::X * 0.5,
+                Vec3::new(2.0, 0.0, 0.0),
+                Vec3::X * 10.0,
+            ),
+            // Projectile standing still
+            (Vec3::ZERO, Vec3::ZERO, Vec3::new(0.0, 2.0, 0.0), Vec3::ZERO),
+        ];
+
+        for mode in MODES {
+            for (position, velocity, target_position, target_velocity) in cases {
+                let direction = desired_direction(
+                    mode,
+                    Vec3::X,
+                    position,
+                    velocity,
+                    target_position,
+                    target_velocity,
+                    delta_secs,
+                );
+                assert_finite(direction);
+                assert!(
+                    direction.length() > 0.5,
+                    "{mode:?} returned a null direction"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn proportional_navigation_keeps_course_without_closing_velocity() {
+        // Projectile and target flying side by side: the line of sight does not rotate
+        let direction = desired_direction(
+            GuidanceMode::ProportionalNavigation { gain: 4.0 },
+            Vec3::X,
+            Vec3::ZERO,
+            Vec3::X,
+            Vec3::new(2.0, 1.0, 0.0),
+            Vec3::X,
+            1.0 / 60.0,
+        );
+        assert_eq!(direction, Vec3::X);
+    }
+}
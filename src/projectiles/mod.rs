@@ -1,7 +1,12 @@
+pub mod assets;
 pub mod cannon_ball;
+pub mod guidance;
+pub mod modifiers;
 pub mod projectile;
 pub mod rocket;
 
-pub use cannon_ball::spawn_cannon_ball_projectile;
+pub use assets::ProjectileAssets;
+pub use cannon_ball::{spawn_cannon_ball_projectile, spawn_flak_projectile};
+pub use modifiers::{Piercing, Ricochet};
 pub use projectile::*;
 pub use rocket::spawn_rocket_projectile;
@@ -0,0 +1,174 @@
+use crate::play_area::PlayArea;
+use crate::projectiles::Projectile;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// Lets a projectile pass through several targets before being destroyed
+#[derive(Component, Default)]
+pub struct Piercing {
+    /// Number of additional targets the projectile can pass through
+    pub remaining: u32,
+    /// Entities already hit by this projectile, they are never hit twice
+    pub hit_entities: Vec<Entity>,
+}
+
+impl Piercing {
+    pub fn new(count: u32) -> Self {
+        Self {
+            remaining: count,
+            hit_entities: Vec::new(),
+        }
+    }
+
+    /// Check if the projectile already went through the given entity
+    pub fn has_hit(&self, entity: Entity) -> bool {
+        self.hit_entities.contains(&entity)
+    }
+
+    /// Record a hit on the given entity, returns true if the projectile survives it
+    pub fn register_hit(&mut self, entity: Entity) -> bool {
+        self.hit_entities.push(entity);
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Lets a projectile reflect off screen edges and obstacles a limited number of times
+#[derive(Component, Default)]
+pub struct Ricochet {
+    /// Number of bounces left before the projectile behaves normally
+    pub remaining_bounces: u32,
+    /// Surface normal of an obstacle hit this frame, applied by bounce_ricochet_projectiles
+    pub pending_normal: Option<Vec3>,
+    /// Last obstacle the projectile bounced off, ignored until the next bounce
+    pub last_obstacle: Option<Entity>,
+}
+
+impl Ricochet {
+    pub fn new(bounces: u32) -> Self {
+        Self {
+            remaining_bounces: bounces,
+            pending_normal: None,
+            last_obstacle: None,
+        }
+    }
+}
+
+/// Check if a projectile modifier makes the projectile ignore the given target
+pub fn ignores_target(
+    projectile: Entity,
+    target: Entity,
+    piercings: &Query<&mut Piercing>,
+    ricochets: &Query<&mut Ricochet>,
+) -> bool {
+    if let Ok(piercing) = piercings.get(projectile) {
+        if piercing.has_hit(target) {
+            return true;
+        }
+    }
+    if let Ok(ricochet) = ricochets.get(projectile) {
+        if ricochet.last_obstacle == Some(target) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Let the projectile modifiers absorb a hit on the given target.
+/// Returns true if the projectile survives the hit and should not take damage.
+pub fn absorb_hit(
+    projectile: Entity,
+    target: Entity,
+    piercings: &mut Query<&mut Piercing>,
+    ricochets: &mut Query<&mut Ricochet>,
+    transforms: &Query<&GlobalTransform>,
+) -> bool {
+    // Piercing projectiles go through the target
+    if let Ok(mut piercing) = piercings.get_mut(projectile) {
+        if piercing.register_hit(target) {
+            return true;
+        }
+    }
+
+    // Ricochet projectiles bounce off the target
+    if let Ok(mut ricochet) = ricochets.get_mut(projectile) {
+        if ricochet.remaining_bounces > 0 {
+            if let (Ok(projectile_transform), Ok(target_transform)) =
+                (transforms.get(projectile), transforms.get(target))
+            {
+                // Approximate the contact normal with the direction between both centers
+                let normal = (projectile_transform.translation() - target_transform.translation())
+                    .normalize_or_zero();
+                if normal != Vec3::ZERO {
+                    ricochet.remaining_bounces -= 1;
+                    ricochet.pending_normal = Some(normal);
+                    ricochet.last_obstacle = Some(target);
+                    return true;
+                }
+            }
+        }
+    }
+
+    false
+}
+
+/// Returns the normal of the play area edge the position is crossing, if the velocity points outward
+fn screen_edge_normal(play_area: &PlayArea, position: Vec3, velocity: Vec3) -> Option<Vec3> {
+    if position.x < play_area.left() && velocity.x < 0.0 {
+        Some(Vec3::X)
+    } else if position.x > play_area.right() && velocity.x > 0.0 {
+        Some(Vec3::NEG_X)
+    } else if position.y > play_area.top() && velocity.y > 0.0 {
+        Some(Vec3::NEG_Y)
+    } else if position.y < play_area.bottom() && velocity.y < 0.0 {
+        Some(Vec3::Y)
+    } else {
+        None
+    }
+}
+
+/// Reflects ricochet projectiles off obstacles they hit and off the screen edges
+pub fn bounce_ricochet_projectiles(
+    mut projectiles: Query<(
+        &mut Ricochet,
+        &mut Projectile,
+        &mut Velocity,
+        &mut Transform,
+    )>,
+    play_area: Res<PlayArea>,
+) {
+    for (mut ricochet, mut projectile, mut velocity, mut transform) in projectiles.iter_mut() {
+        // Obstacle bounces were already counted by the collision system
+        let mut normal = ricochet.pending_normal.take();
+
+        if normal.is_none() && ricochet.remaining_bounces > 0 {
+            normal = screen_edge_normal(&play_area, transform.translation, velocity.linvel);
+            if normal.is_some() {
+                ricochet.remaining_bounces -= 1;
+                ricochet.last_obstacle = None;
+            }
+        }
+
+        if let Some(normal) = normal {
+            // Only reflect velocities going into the surface
+            if velocity.linvel.dot(normal) < 0.0 {
+                velocity.linvel = velocity.linvel.reflect(normal);
+            }
+            if projectile.direction.dot(normal) < 0.0 {
+                projectile.direction = projectile
+                    .direction
+                    .reflect(normal)
+                    .normalize_or(projectile.direction);
+
+                // Keep the mesh pointing along the new direction
+                let mut temp_transform = Transform::IDENTITY;
+                temp_transform.look_to(projectile.direction, Vec3::Y);
+                transform.rotation = temp_transform.rotation * projectile.mesh_rotation_offset;
+            }
+        }
+    }
+}
@@ -1,7 +1,30 @@
+use crate::collision::{Collidable, apply_area_damage};
 use crate::enemies::Enemy;
+use crate::particles::TrailEmitter;
+use crate::play_area::PlayArea;
+use crate::pool::{EntityPool, Pooled, despawn_or_release};
+use crate::projectiles::assets::{ProjectileAssets, setup_projectile_assets};
+use crate::projectiles::guidance::{GuidanceMode, desired_direction, rotate_toward};
+use crate::projectiles::modifiers::bounce_ricochet_projectiles;
+use crate::spatial::{SpatialIndex, SpatialKind};
+use crate::weapons::weapon::ProjectileSpawner;
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
-use rand::Rng;
+
+/// What happens to a projectile when it reaches its maximum lifetime or range
+#[derive(Clone, Copy)]
+pub enum ProjectileExpiry {
+    /// The projectile silently disappears
+    Fizzle,
+    /// The projectile explodes, damaging the other team's collidables around it
+    Explode { radius: f32, damage: f32 },
+    /// The projectile splits into several projectiles spread around its direction
+    Split {
+        count: u32,
+        spread: f32, // Total spread angle in radians, a full turn spreads evenly all around
+        spawner: ProjectileSpawner,
+    },
+}
 
 #[derive(Component)]
 pub struct Projectile {
@@ -12,6 +35,12 @@ pub struct Projectile {
     pub activation_timer: f32,
     pub enemy: Option<Entity>,
     pub mesh_rotation_offset: Quat,
+    pub guidance: GuidanceMode, // How homing projectiles steer toward their enemy
+    pub max_lifetime: Option<f32>, // Seconds before the projectile expires
+    pub max_distance: Option<f32>, // Distance travelled before the projectile expires
+    pub age: f32,
+    pub distance_travelled: f32,
+    pub expiry: ProjectileExpiry,
 }
 
 impl Default for Projectile {
@@ -24,21 +53,118 @@ impl Default for Projectile {
             activation_timer: 0.0,
             enemy: None,
             mesh_rotation_offset: Quat::IDENTITY,
+            guidance: GuidanceMode::PurePursuit,
+            max_lifetime: Some(10.0), // Default: projectiles never linger more than 10 seconds
+            max_distance: None,
+            age: 0.0,
+            distance_travelled: 0.0,
+            expiry: ProjectileExpiry::Fizzle,
         }
     }
 }
 
-pub fn despawn_out_of_bounds_projectiles(
+impl Projectile {
+    /// Check if the projectile exceeded its maximum lifetime or range
+    pub fn is_expired(&self) -> bool {
+        self.max_lifetime
+            .is_some_and(|lifetime| self.age >= lifetime)
+            || self
+                .max_distance
+                .is_some_and(|distance| self.distance_travelled >= distance)
+    }
+}
+
+pub fn update_projectile_lifetimes(
+    mut projectiles: Query<(&mut Projectile, &Velocity)>,
+    time: Res<Time>,
+) {
+    for (mut projectile, velocity) in projectiles.iter_mut() {
+        projectile.age += time.delta_secs();
+        projectile.distance_travelled += velocity.linvel.length() * time.delta_secs();
+    }
+}
+
+/// Despawn projectiles that exceeded their lifetime or range, applying their expiry behaviour
+pub fn expire_projectiles(
     mut commands: Commands,
-    projectiles: Query<(Entity, &Transform), With<Projectile>>,
+    projectiles: Query<(Entity, &Projectile, &Transform, &Velocity, Option<&Pooled>)>,
+    mut targets: Query<(Entity, &GlobalTransform, &mut Collidable)>,
+    projectile_assets: Res<ProjectileAssets>,
+    mut pool: ResMut<EntityPool>,
+    asset_server: Res<AssetServer>,
+    mut scene_spawner: ResMut<SceneSpawner>,
 ) {
-    let boundary = 50.0; // Distance from origin before despawning
+    for (entity, projectile, transform, velocity, pooled) in projectiles.iter() {
+        // Projectiles already removed this frame by a hit do not split or explode
+        if !projectile.is_expired() || pool.is_released(entity) {
+            continue;
+        }
 
-    for (entity, transform) in projectiles.iter() {
-        let pos = transform.translation;
-        // Despawn if projectile is too far in any direction
-        if pos.x.abs() > boundary || pos.y.abs() > boundary || pos.z.abs() > boundary {
-            commands.entity(entity).despawn();
+        // Projectiles share their team with the collidable component
+        let Ok((_, _, collidable)) = targets.get(entity) else {
+            despawn_or_release(&mut commands, &mut pool, entity, pooled);
+            continue;
+        };
+        let team = collidable.team;
+        let position = transform.translation;
+
+        match projectile.expiry {
+            ProjectileExpiry::Fizzle => {}
+            ProjectileExpiry::Explode { radius, damage } => {
+                apply_area_damage(position, radius, damage, team, &[entity], &mut targets);
+            }
+            ProjectileExpiry::Split {
+                count,
+                spread,
+                spawner,
+            } => {
+                let speed = velocity.linvel.length();
+                let base_direction = velocity.linvel.normalize_or(projectile.direction);
+
+                for i in 0..count {
+                    // Spread fragments evenly, a full turn must not put two fragments at the same angle
+                    let angle = if spread >= std::f32::consts::TAU {
+                        spread * i as f32 / count as f32
+                    } else if count > 1 {
+                        -spread / 2.0 + spread * i as f32 / (count - 1) as f32
+                    } else {
+                        0.0
+                    };
+                    // Game plays on the XY plane, so fragments spread around the Z axis
+                    let direction = Quat::from_rotation_z(angle) * base_direction;
+
+                    spawner(
+                        &mut commands,
+                        &projectile_assets,
+                        &mut pool,
+                        &asset_server,
+                        &mut scene_spawner,
+                        position,
+                        direction * speed,
+                        // Weapons fire along their X axis
+                        Quat::from_rotation_arc(Vec3::X, direction),
+                        team,
+                    );
+                }
+            }
+        }
+
+        despawn_or_release(&mut commands, &mut pool, entity, pooled);
+    }
+}
+
+/// Silently remove projectiles that left the play area, they can no longer hit anything on screen
+pub fn cull_offscreen_projectiles(
+    mut commands: Commands,
+    projectiles: Query<(Entity, &Transform, Option<&Pooled>), With<Projectile>>,
+    play_area: Res<PlayArea>,
+    mut pool: ResMut<EntityPool>,
+) {
+    let cull_margin = 1.0; // Homing projectiles may loop back from just off-screen
+
+    for (entity, transform, pooled) in projectiles.iter() {
+        if !play_area.contains(transform.translation, cull_margin) {
+            despawn_or_release(&mut commands, &mut pool, entity, pooled);
         }
     }
 }
@@ -58,21 +184,31 @@ pub fn update_projectile_activation_timers(
 }
 
 pub fn select_projectile_enemies(
-    mut projectiles: Query<(Entity, &mut Projectile, &Transform)>,
-    enemies: Query<Entity, (With<Enemy>, With<Transform>)>,
+    mut projectiles: Query<(&mut Projectile, &Transform)>,
+    spatial_index: Res<SpatialIndex>,
 ) {
-    let mut rng = rand::thread_rng();
-    let enemy_entities: Vec<Entity> = enemies.iter().collect();
-
-    if enemy_entities.is_empty() {
-        return;
-    }
+    let cone_half_angle = std::f32::consts::FRAC_PI_4; // Prefer enemies within 45 degrees ahead
+    let range = 10.0; // Homing projectiles ignore enemies further than this
 
-    for (_projectile_entity, mut projectile, _transform) in projectiles.iter_mut() {
+    for (mut projectile, transform) in projectiles.iter_mut() {
         if projectile.homing && projectile.activation_timer <= 0.0 && projectile.enemy.is_none() {
-            // Randomly select an enemy
-            let random_index = rng.gen_range(0..enemy_entities.len());
-            projectile.enemy = Some(enemy_entities[random_index]);
+            // Select the nearest enemy ahead, or the nearest enemy around if none is ahead
+            let target = spatial_index
+                .nearest_in_cone(
+                    transform.translation,
+                    projectile.direction,
+                    cone_half_angle,
+                    range,
+                    SpatialKind::Enemy,
+                )
+                .or_else(|| {
+                    spatial_index.nearest_in_radius(
+                        transform.translation,
+                        range,
+                        SpatialKind::Enemy,
+                    )
+                });
+            projectile.enemy = target.map(|entry| entry.entity);
         }
     }
 }
@@ -90,57 +226,57 @@ pub fn apply_projectile_acceleration(
     }
 }
 
+/// Light the trail of projectiles whose motor is running, behind the projectile
+pub fn update_projectile_trails(mut projectiles: Query<(&Projectile, &mut TrailEmitter)>) {
+    let nozzle_distance = 0.08; // Distance from the projectile's center to its exhaust
+
+    for (projectile, mut trail) in projectiles.iter_mut() {
+        // Same condition as apply_projectile_acceleration
+        trail.active = projectile.homing
+            && projectile.activation_timer <= 0.0
+            && projectile.acceleration > 0.0;
+        trail.direction = -projectile.direction;
+        trail.offset = -projectile.direction * nozzle_distance;
+    }
+}
+
 pub fn steer_projectiles_toward_enemy(
-    mut projectiles: Query<(&mut Projectile, &mut Transform)>,
-    enemies: Query<&Transform, (With<Enemy>, Without<Projectile>)>,
+    mut projectiles: Query<(&mut Projectile, &mut Transform, &Velocity)>,
+    enemies: Query<(&Transform, Option<&Velocity>), (With<Enemy>, Without<Projectile>)>,
     time: Res<Time>,
 ) {
-    for (mut projectile, mut transform) in projectiles.iter_mut() {
+    for (mut projectile, mut transform, velocity) in projectiles.iter_mut() {
         if !projectile.homing {
             continue;
         }
 
         if let Some(enemy_entity) = projectile.enemy {
             // Check if enemy still exists
-            if let Ok(enemy_transform) = enemies.get(enemy_entity) {
-                let projectile_pos = transform.translation;
-                let enemy_pos = enemy_transform.translation;
-
-                // Calculate desired direction to enemy
-                let desired_direction = (enemy_pos - projectile_pos).normalize();
-
-                // Rotate current direction toward desired direction using agility
-                let current_direction = projectile.direction;
-                let angle_between = current_direction.dot(desired_direction).acos();
-
-                if angle_between > 0.001 {
-                    // Calculate maximum rotation this frame
-                    let max_rotation = projectile.agility * time.delta_secs();
-                    let rotation_amount = angle_between.min(max_rotation);
-
-                    // Use slerp to rotate toward enemy
-                    let t = rotation_amount / angle_between;
-                    projectile.direction = current_direction.lerp(desired_direction, t).normalize();
-
-                    // Update transform rotation to match direction
-                    // Use looking_to to create rotation that points in the direction vector
-                    if projectile.direction.length_squared() > 0.001 {
-                        // Create a temporary transform to calculate the base rotation
-                        let mut temp_transform = Transform::IDENTITY;
-                        temp_transform.look_to(projectile.direction, Vec3::Y);
-                        // Apply the mesh rotation offset to preserve the initial mesh orientation
-                        transform.rotation =
-                            temp_transform.rotation * projectile.mesh_rotation_offset;
-                    }
-                } else {
-                    // Already pointing at enemy, just update direction
-                    projectile.direction = desired_direction;
-                    // Update rotation to match
-                    let mut temp_transform = Transform::IDENTITY;
-                    temp_transform.look_to(desired_direction, Vec3::Y);
-                    // Apply the mesh rotation offset to preserve the initial mesh orientation
-                    transform.rotation = temp_transform.rotation * projectile.mesh_rotation_offset;
-                }
+            if let Ok((enemy_transform, enemy_velocity)) = enemies.get(enemy_entity) {
+                let enemy_linvel = enemy_velocity.map_or(Vec3::ZERO, |velocity| velocity.linvel);
+
+                // Calculate desired direction using the projectile's guidance mode
+                let target_direction = desired_direction(
+                    projectile.guidance,
+                    projectile.direction,
+                    transform.translation,
+                    velocity.linvel,
+                    enemy_transform.translation,
+                    enemy_linvel,
+                    time.delta_secs(),
+                );
+
+                // Rotate current direction toward desired direction, limited by agility
+                let max_rotation = projectile.agility * time.delta_secs();
+                projectile.direction =
+                    rotate_toward(projectile.direction, target_direction, max_rotation);
+
+                // Update transform rotation to match direction
+                // Create a temporary transform to calculate the base rotation
+                let mut temp_transform = Transform::IDENTITY;
+                temp_transform.look_to(projectile.direction, Vec3::Y);
+                // Apply the mesh rotation offset to preserve the initial mesh orientation
+                transform.rotation = temp_transform.rotation * projectile.mesh_rotation_offset;
             } else {
                 // Enemy was despawned, clear enemy
                 projectile.enemy = None;
@@ -153,16 +289,21 @@ pub struct ProjectilePlugin;
 
 impl Plugin for ProjectilePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                update_projectile_activation_timers,
-                select_projectile_enemies,
-                apply_projectile_acceleration,
-                steer_projectiles_toward_enemy,
-                despawn_out_of_bounds_projectiles,
-            )
-                .chain(),
-        );
+        app.add_systems(Startup, setup_projectile_assets)
+            .add_systems(
+                Update,
+                (
+                    update_projectile_activation_timers,
+                    select_projectile_enemies,
+                    apply_projectile_acceleration,
+                    steer_projectiles_toward_enemy,
+                    update_projectile_trails,
+                    bounce_ricochet_projectiles,
+                    update_projectile_lifetimes,
+                    expire_projectiles,
+                    cull_offscreen_projectiles,
+                )
+                    .chain(),
+            );
     }
 }
@@ -1,53 +1,81 @@
 use crate::collision::{Collidable, Team};
-use crate::projectiles::Projectile;
+use crate::fallback::{FallbackShape, SceneFallback};
+use crate::particles::{ParticleEmitter, TrailEmitter};
+use crate::pool::{EntityPool, PoolKind, acquire_or_spawn};
+use crate::projectiles::guidance::GuidanceMode;
+use crate::projectiles::{Projectile, ProjectileAssets, ProjectileExpiry};
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
 /// Spawns a rocket projectile using the rocket.glb mesh
 pub fn spawn_rocket_projectile(
     commands: &mut Commands,
-    _meshes: &mut ResMut<Assets<Mesh>>,
-    _materials: &mut ResMut<Assets<StandardMaterial>>,
-    asset_server: &Res<AssetServer>,
+    projectile_assets: &Res<ProjectileAssets>,
+    pool: &mut ResMut<EntityPool>,
+    _asset_server: &Res<AssetServer>,
     _scene_spawner: &mut ResMut<SceneSpawner>,
     position: Vec3,
     velocity: Vec3,
     rotation: Quat,
     team: Team,
-) {
-    let rocket_scene_handle = asset_server.load("models/projectiles/rocket.glb#Scene0");
-
+) -> Entity {
     // Calculate forward direction from rotation
     let rocket_rotation = rotation * Quat::from_rotation_y(std::f32::consts::FRAC_PI_2);
     let forward_direction = rocket_rotation * Vec3::Z;
 
-    commands.spawn((
-        Projectile {
-            acceleration: 5.0, // Acceleration for rockets
-            agility: 1.0,      // Turn rate in radians per second
-            direction: forward_direction.normalize(),
-            homing: true,          // Rockets are homing projectiles
-            activation_timer: 1.0, // Start with 1 second cooldown
-            enemy: None,          // No enemy initially
-            mesh_rotation_offset: Quat::from_rotation_y(std::f32::consts::PI), // 90-degree Y rotation for rocket mesh
-        },
-        Collidable::new(25.0, 1.0, team), // 25 damage, 1 HP, use passed team
-        Velocity::linear(velocity),
-        Damping {
-            linear_damping: 0.6,
-            angular_damping: 0.0,
-        },
-        RigidBody::KinematicVelocityBased,
-        SceneRoot(rocket_scene_handle),
-        AsyncSceneCollider {
-            shape: Some(ComputedColliderShape::ConvexHull),
-            named_shapes: Default::default(),
-        },
-        ActiveEvents::COLLISION_EVENTS,
-        Transform {
-            translation: position,
-            rotation: rocket_rotation,
-            scale: Vec3::splat(0.0002), // Scale down the rocket
-        },
-    ));
+    let (projectile_entity, fresh) = acquire_or_spawn(
+        commands,
+        pool,
+        PoolKind::Rocket,
+        (
+            Projectile {
+                acceleration: 5.0, // Acceleration for rockets
+                agility: 1.0,      // Turn rate in radians per second
+                direction: forward_direction.normalize(),
+                homing: true,          // Rockets are homing projectiles
+                activation_timer: 1.0, // Start with 1 second cooldown
+                enemy: None,           // No enemy initially
+                mesh_rotation_offset: Quat::from_rotation_y(std::f32::consts::PI), // 90-degree Y rotation for rocket mesh
+                // Rockets lead their target instead of chasing it
+                guidance: GuidanceMode::ProportionalNavigation { gain: 3.0 },
+                max_lifetime: Some(6.0), // Rockets run out of fuel after 6 seconds...
+                // ...and explode, damaging enemies around them
+                expiry: ProjectileExpiry::Explode {
+                    radius: 0.5,
+                    damage: 15.0,
+                },
+                ..default()
+            },
+            Collidable::new(25.0, 1.0, team), // 25 damage, 1 HP, use passed team
+            // Lit by update_projectile_trails once the motor starts
+            TrailEmitter::new(ParticleEmitter::rocket_trail(), 60.0)
+                .with_ignition(ParticleEmitter::explosion().scaled(0.25)),
+            Velocity::linear(velocity),
+            Transform {
+                translation: position,
+                rotation: rocket_rotation,
+                scale: Vec3::splat(0.0002), // Scale down the rocket
+            },
+        ),
+    );
+
+    // Recycled rockets keep their scene and the colliders built from it
+    if fresh {
+        commands.entity(projectile_entity).insert((
+            Damping {
+                linear_damping: 0.6,
+                angular_damping: 0.0,
+            },
+            RigidBody::KinematicVelocityBased,
+            SceneRoot(projectile_assets.rocket_scene.clone()),
+            SceneFallback(FallbackShape::Rocket),
+            AsyncSceneCollider {
+                shape: Some(ComputedColliderShape::ConvexHull),
+                named_shapes: Default::default(),
+            },
+            ActiveEvents::COLLISION_EVENTS,
+        ));
+    }
+
+    projectile_entity
 }
@@ -1,6 +1,13 @@
+use crate::camera_shake::CameraShake;
 use crate::collision::{Collidable, Persistent, Team};
+use crate::fallback::{FallbackShape, SceneFallback};
+use crate::particles::{ParticleBurst, ParticleEmitter, TrailEmitter};
+use crate::play_area::PlayArea;
+use crate::pool::EntityPool;
+use crate::projectiles::ProjectileAssets;
 use crate::weapons::cannon::create_cannon;
 use crate::weapons::create_rocket_launcher;
+use crate::weapons::railgun::create_railgun;
 use crate::weapons::weapon::{Weapon, WeaponMesh, attach_weapon, fire_weapon};
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
@@ -45,14 +52,16 @@ impl Default for PlayerLives {
 pub fn remove_weapon(
     commands: &mut Commands,
     ship_entity: Entity,
-    weapon_meshes: &Query<Entity, With<WeaponMesh>>,
+    weapon_meshes: &Query<(Entity, &ChildOf), With<WeaponMesh>>,
 ) {
     // Remove the Weapon component from the ship entity
     commands.entity(ship_entity).remove::<Weapon>();
 
-    // Despawn any weapon mesh entities
-    for weapon_mesh_entity in weapon_meshes.iter() {
-        commands.entity(weapon_mesh_entity).despawn();
+    // Despawn the ship's weapon mesh entities, enemies keep theirs
+    for (weapon_mesh_entity, child_of) in weapon_meshes.iter() {
+        if child_of.parent() == ship_entity {
+            commands.entity(weapon_mesh_entity).despawn();
+        }
     }
 }
 
@@ -62,7 +71,7 @@ pub fn switch_weapon(
     asset_server: &Res<AssetServer>,
     scene_spawner: &mut ResMut<SceneSpawner>,
     ship_entity: Entity,
-    weapon_meshes: &Query<Entity, With<WeaponMesh>>,
+    weapon_meshes: &Query<(Entity, &ChildOf), With<WeaponMesh>>,
     new_weapon: Weapon,
 ) {
     // Remove current weapon
@@ -106,7 +115,9 @@ pub fn setup_ship(
             RigidBody::KinematicVelocityBased,
             ActiveEvents::COLLISION_EVENTS,
             ActiveCollisionTypes::KINEMATIC_KINEMATIC,
+            TrailEmitter::new(ParticleEmitter::exhaust(), 80.0), // Lit while accelerating
             SceneRoot(spaceship_handle),
+            SceneFallback(FallbackShape::Ship),
             AsyncSceneCollider {
                 shape: Some(ComputedColliderShape::ConvexHull),
                 named_shapes: Default::default(),
@@ -133,12 +144,11 @@ pub fn setup_ship(
 pub fn update_ship_velocity(
     keyboard_input: Res<ButtonInput<KeyCode>>,
     spaceship_entity: Res<SpaceshipEntity>,
-    mut query: Query<(&mut Velocity, &Transform)>,
-    camera_query: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
-    windows: Query<&Window>,
+    mut query: Query<(&mut Velocity, &mut Transform, &mut TrailEmitter)>,
+    play_area: Res<PlayArea>,
     time: Res<Time>,
 ) {
-    if let Ok((mut velocity, transform)) = query.get_mut(spaceship_entity.0) {
+    if let Ok((mut velocity, mut transform, mut exhaust)) = query.get_mut(spaceship_entity.0) {
         let acceleration_rate = 5.0; // Acceleration rate
         let mut accel_vector = Vec3::ZERO;
 
@@ -161,42 +171,22 @@ pub fn update_ship_velocity(
             accel_vector.x += acceleration_rate;
         }
 
-        // Viewport boundary system - prevent ship from going out of bounds
-        // Get window dimensions for resolution-independent calculations
-        if let Ok(window) = windows.single() {
-            let window_width = window.width();
-            let window_height = window.height();
-            let boundary_margin = 0.05; // Margin from viewport edge (5% of viewport) - kept for reference
-
-            // Get camera for screen coordinate calculations
-            if let Ok((camera, camera_global_transform)) = camera_query.single() {
-                // Convert world position to screen coordinates
-                if let Ok(viewport_pos) =
-                    camera.world_to_viewport(camera_global_transform, transform.translation)
-                {
-                    // Convert normalized viewport coordinates to screen pixels
-                    let screen_x = viewport_pos.x / window_width;
-                    let screen_y = viewport_pos.y / window_height;
+        // Apply acceleration to velocity
+        velocity.linvel += accel_vector * time.delta_secs();
 
-                    // Check X boundaries in screen pixels
-                    if screen_x > 1.0 - boundary_margin && velocity.linvel.x > 0.0 {
-                        velocity.linvel.x = 0.0; // Zero out velocity going out of bounds to the right
-                    } else if screen_x < boundary_margin && velocity.linvel.x < 0.0 {
-                        velocity.linvel.x = 0.0; // Zero out velocity going out of bounds to the left
-                    }
+        // Keep the ship inside the play area, even when moving fast
+        let edge_margin = 0.2; // Distance kept from the edges of the screen
+        transform.translation = play_area.clamp(transform.translation, edge_margin);
+        velocity.linvel =
+            play_area.stop_at_edges(transform.translation, velocity.linvel, edge_margin);
 
-                    // Check Y boundaries in screen pixels
-                    if screen_y > 1.0 - boundary_margin && velocity.linvel.y < 0.0 {
-                        velocity.linvel.y = 0.0; // Zero out velocity going out of bounds upward
-                    } else if screen_y < boundary_margin && velocity.linvel.y > 0.0 {
-                        velocity.linvel.y = 0.0; // Zero out velocity going out of bounds downward
-                    }
-                }
-            }
+        // Fire the engine opposite to the acceleration
+        let engine_distance = 0.15; // Distance from the ship's center to the flame
+        exhaust.active = accel_vector != Vec3::ZERO;
+        if let Some(direction) = (-accel_vector).try_normalize() {
+            exhaust.direction = direction;
+            exhaust.offset = direction * engine_distance;
         }
-
-        // Apply acceleration to velocity
-        velocity.linvel += accel_vector * time.delta_secs();
     }
 }
 
@@ -232,7 +222,7 @@ pub fn switch_weapon_input(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut scene_spawner: ResMut<SceneSpawner>,
-    weapon_meshes: Query<Entity, With<WeaponMesh>>,
+    weapon_meshes: Query<(Entity, &ChildOf), With<WeaponMesh>>,
 ) {
     // Press 1 for cannon
     if keyboard_input.just_pressed(KeyCode::Digit1) {
@@ -259,6 +249,19 @@ pub fn switch_weapon_input(
             rocket_weapon,
         );
     }
+
+    // Press 3 for railgun
+    if keyboard_input.just_pressed(KeyCode::Digit3) {
+        let railgun_weapon = create_railgun(Vec3::new(0.0, 0.0, 0.0));
+        switch_weapon(
+            &mut commands,
+            &asset_server,
+            &mut scene_spawner,
+            spaceship_entity.0,
+            &weapon_meshes,
+            railgun_weapon,
+        );
+    }
 }
 
 pub fn activate_weapon(
@@ -269,8 +272,8 @@ pub fn activate_weapon(
     velocities: Query<&Velocity>,
     collidables: Query<&Collidable>,
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    projectile_assets: Res<ProjectileAssets>,
+    mut pool: ResMut<EntityPool>,
     asset_server: Res<AssetServer>,
     mut scene_spawner: ResMut<SceneSpawner>,
 ) {
@@ -285,8 +288,8 @@ pub fn activate_weapon(
                 &velocities,
                 &collidables,
                 &mut commands,
-                &mut meshes,
-                &mut materials,
+                &projectile_assets,
+                &mut pool,
                 &asset_server,
                 &mut scene_spawner,
             );
@@ -298,11 +301,23 @@ pub fn handle_ship_death(
     mut query: Query<(&mut Collidable, &mut Transform, &mut Velocity), With<Ship>>,
     mut player_lives: ResMut<PlayerLives>,
     spaceship_entity: Res<SpaceshipEntity>,
+    mut bursts: MessageWriter<ParticleBurst>,
+    mut camera_shake: ResMut<CameraShake>,
 ) {
     if let Ok((mut collidable, mut transform, mut velocity)) = query.get_mut(spaceship_entity.0) {
         if !collidable.is_alive() {
             if player_lives.lives > 0 {
                 player_lives.lives -= 1;
+                // Leave a wreck where the ship died
+                bursts.write(ParticleBurst::new(
+                    transform.translation,
+                    ParticleEmitter::explosion().scaled(2.0),
+                ));
+                bursts.write(ParticleBurst::new(
+                    transform.translation,
+                    ParticleEmitter::debris().scaled(2.0),
+                ));
+                camera_shake.add_trauma(0.8);
                 // Reset ship
                 collidable.hit_points = collidable.max_hit_points;
                 transform.translation = Vec3::ZERO;
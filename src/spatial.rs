@@ -0,0 +1,145 @@
+use crate::enemies::Enemy;
+use crate::ship::Ship;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// The kind of entity stored in the spatial index
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SpatialKind {
+    Enemy,
+    Ship,
+}
+
+/// An entity stored in the spatial index, with its position when the index was built
+#[derive(Clone, Copy, Debug)]
+pub struct SpatialEntry {
+    pub entity: Entity,
+    pub position: Vec3,
+    pub kind: SpatialKind,
+}
+
+/// A uniform grid over the XY play plane, rebuilt every frame from enemy and ship transforms
+#[derive(Resource)]
+pub struct SpatialIndex {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<SpatialEntry>>,
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        Self::new(1.0) // Roughly the size of a few drones
+    }
+}
+
+impl SpatialIndex {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Remove every entry, keeping the allocated cells
+    pub fn clear(&mut self) {
+        for entries in self.cells.values_mut() {
+            entries.clear();
+        }
+    }
+
+    pub fn insert(&mut self, entity: Entity, position: Vec3, kind: SpatialKind) {
+        let cell = self.cell(position);
+        self.cells.entry(cell).or_default().push(SpatialEntry {
+            entity,
+            position,
+            kind,
+        });
+    }
+
+    fn cell(&self, position: Vec3) -> IVec2 {
+        (position.truncate() / self.cell_size).floor().as_ivec2()
+    }
+
+    /// Iterate the entries of the given kind in every cell overlapping the circle
+    fn candidates(
+        &self,
+        center: Vec3,
+        radius: f32,
+        kind: SpatialKind,
+    ) -> impl Iterator<Item = &SpatialEntry> {
+        let min = self.cell(center - Vec3::splat(radius));
+        let max = self.cell(center + Vec3::splat(radius));
+        (min.x..=max.x)
+            .flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+            .filter_map(move |cell| self.cells.get(&cell))
+            .flatten()
+            .filter(move |entry| entry.kind == kind)
+    }
+
+    /// Find the nearest entity of the given kind within the radius
+    pub fn nearest_in_radius(
+        &self,
+        center: Vec3,
+        radius: f32,
+        kind: SpatialKind,
+    ) -> Option<SpatialEntry> {
+        self.candidates(center, radius, kind)
+            .map(|entry| (entry, entry.position.distance_squared(center)))
+            .filter(|(_, distance_squared)| *distance_squared <= radius * radius)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(entry, _)| *entry)
+    }
+
+    /// Find the nearest entity of the given kind within range and at most `half_angle`
+    /// radians away from the direction
+    pub fn nearest_in_cone(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        half_angle: f32,
+        range: f32,
+        kind: SpatialKind,
+    ) -> Option<SpatialEntry> {
+        let direction = direction.try_normalize()?;
+        let min_cos = half_angle.cos();
+
+        self.candidates(origin, range, kind)
+            .filter_map(|entry| {
+                let offset = entry.position - origin;
+                let distance_squared = offset.length_squared();
+                if distance_squared > range * range {
+                    return None;
+                }
+                // An entity exactly at the origin is considered inside the cone
+                let inside = offset
+                    .try_normalize()
+                    .is_none_or(|offset_direction| offset_direction.dot(direction) >= min_cos);
+                inside.then_some((entry, distance_squared))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(entry, _)| *entry)
+    }
+}
+
+/// Rebuild the spatial index from the current enemy and ship positions
+pub fn rebuild_spatial_index(
+    mut index: ResMut<SpatialIndex>,
+    enemies: Query<(Entity, &GlobalTransform), With<Enemy>>,
+    ships: Query<(Entity, &GlobalTransform), With<Ship>>,
+) {
+    index.clear();
+    for (entity, transform) in enemies.iter() {
+        index.insert(entity, transform.translation(), SpatialKind::Enemy);
+    }
+    for (entity, transform) in ships.iter() {
+        index.insert(entity, transform.translation(), SpatialKind::Ship);
+    }
+}
+
+pub struct SpatialPlugin;
+
+impl Plugin for SpatialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpatialIndex>()
+            .add_systems(PreUpdate, rebuild_spatial_index);
+    }
+}
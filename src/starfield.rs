@@ -1,87 +1,194 @@
+use crate::ship::Ship;
 use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
 use rand::Rng;
 
-#[derive(Component)]
-pub struct Star;
+/// A depth band of the starfield, nearer layers scroll faster to give a sense of depth.
+/// Depths are fractions of StarfieldConfig::field_size behind the gameplay plane.
+#[derive(Clone, Copy)]
+pub struct StarLayer {
+    pub near_depth: f32,
+    pub far_depth: f32,
+    pub speed_factor: f32, // Fraction of the scroll speed
+    pub share: f32,        // Fraction of the stars in this layer
+}
+
+pub const STAR_LAYERS: [StarLayer; 3] = [
+    StarLayer {
+        near_depth: 0.1,
+        far_depth: 0.3,
+        speed_factor: 1.0,
+        share: 0.25,
+    },
+    StarLayer {
+        near_depth: 0.3,
+        far_depth: 0.6,
+        speed_factor: 0.5,
+        share: 0.35,
+    },
+    StarLayer {
+        near_depth: 0.6,
+        far_depth: 1.0,
+        speed_factor: 0.2,
+        share: 0.4,
+    },
+];
+
+/// How much faster stars scroll at the peak of a warp
+pub const WARP_SPEED_MULTIPLIER: f32 = 30.0;
+/// How much stars stretch into streaks at the peak of a warp
+pub const WARP_STREAK_LENGTH: f32 = 25.0;
+
+/// Size and density of the starfield
+#[derive(Resource, Clone)]
+pub struct StarfieldConfig {
+    pub star_count: usize,
+    /// Half the size of the cube stars are spread in, stars wrap around at its faces
+    pub field_size: f32,
+    pub star_radius: f32,
+    /// Number of shared star materials, from dimmest to brightest
+    pub brightness_levels: usize,
+}
+
+impl Default for StarfieldConfig {
+    fn default() -> Self {
+        Self {
+            star_count: 1000,
+            field_size: 50.0,
+            star_radius: 0.05,
+            brightness_levels: 8,
+        }
+    }
+}
 
 #[derive(Component)]
-pub struct Skybox;
+pub struct Star {
+    pub layer: usize, // Index in STAR_LAYERS
+}
+
+/// Scroll speed of the level, in world units per second for the nearest star layer
+#[derive(Resource)]
+pub struct ScrollSpeed {
+    pub speed: f32,
+}
+
+impl Default for ScrollSpeed {
+    fn default() -> Self {
+        Self { speed: 1.0 }
+    }
+}
+
+/// Warp-speed jump between levels: stars speed up and streak, then slow down again
+#[derive(Resource, Default)]
+pub struct Warp {
+    pub remaining: f32,
+    pub duration: f32,
+}
+
+impl Warp {
+    pub fn start(&mut self, duration: f32) {
+        self.duration = duration;
+        self.remaining = duration;
+    }
+
+    /// Warp strength, rising from 0 to 1 halfway through and back to 0 at the end
+    pub fn strength(&self) -> f32 {
+        if self.remaining <= 0.0 || self.duration <= 0.0 {
+            return 0.0;
+        }
+        let progress = 1.0 - self.remaining / self.duration;
+        (progress * std::f32::consts::PI).sin()
+    }
+}
 
 pub fn setup_starfield(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    asset_server: Res<AssetServer>,
-    mut scene_spawner: ResMut<SceneSpawner>,
+    config: Res<StarfieldConfig>,
 ) {
-    // Load and spawn skybox centered at origin (where camera is looking) and make it very large
-    let skybox_handle = asset_server.load("models/environment/deep_space_skybox.glb#Scene0");
-    let skybox_entity = commands
-        .spawn((
-            Skybox,
-            Transform {
-                translation: Vec3::ZERO, // Center at origin
-                rotation: Quat::IDENTITY,
-                scale: Vec3::splat(100.0), // Make it way bigger (100x scale)
-            },
-        ))
-        .id();
-    scene_spawner.spawn_as_child(skybox_handle, skybox_entity);
-
     let mut rng = rand::thread_rng();
-    let star_count = 100;
-    let field_size = 50.0; // Size of the starfield cube
-
-    // Create a small sphere mesh for stars
-    let star_mesh = meshes.add(Sphere::new(0.05));
-
-    // Spawn stars randomly distributed in a cube around the origin
-    for _ in 0..star_count {
-        let x = rng.gen_range(-field_size..field_size);
-        let y = rng.gen_range(-field_size..field_size);
-        let z = rng.gen_range(-field_size..-5.0);
-
-        // Vary star brightness slightly
-        let brightness = rng.gen_range(0.5..1.0);
-        let star_color = Color::srgb(brightness, brightness, brightness);
-
-        // Create a glowing white material for each star
-        let star_material = materials.add(StandardMaterial {
-            base_color: star_color,
-            emissive: star_color.into(),
-            ..default()
-        });
-
-        commands.spawn((
-            Star,
-            Mesh3d(star_mesh.clone()),
-            MeshMaterial3d(star_material),
-            Transform::from_translation(Vec3::new(x, y, z)),
-        ));
+    let field_size = config.field_size;
+
+    // Every star shares one mesh and one of a few materials, so they are drawn in a few batches
+    let star_mesh = meshes.add(Sphere::new(config.star_radius).mesh().uv(8, 6)); // Low poly, stars are tiny
+    let brightness_levels = config.brightness_levels.max(1);
+    let star_materials: Vec<Handle<StandardMaterial>> = (0..brightness_levels)
+        .map(|level| {
+            // Vary star brightness slightly, from 0.5 to 1.0
+            let brightness = 0.5 + 0.5 * (level + 1) as f32 / brightness_levels as f32;
+            let star_color = Color::srgb(brightness, brightness, brightness);
+            materials.add(StandardMaterial {
+                base_color: star_color,
+                emissive: star_color.into(),
+                ..default()
+            })
+        })
+        .collect();
+
+    // Spawn stars randomly distributed in each layer's slice of a cube around the origin
+    for (layer_index, layer) in STAR_LAYERS.iter().enumerate() {
+        let layer_star_count = (config.star_count as f32 * layer.share).round() as usize;
+        for _ in 0..layer_star_count {
+            let x = rng.gen_range(-field_size..field_size);
+            let y = rng.gen_range(-field_size..field_size);
+            let z = -field_size * rng.gen_range(layer.near_depth..layer.far_depth);
+            let material = &star_materials[rng.gen_range(0..brightness_levels)];
+
+            commands.spawn((
+                Star { layer: layer_index },
+                Mesh3d(star_mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_translation(Vec3::new(x, y, z)),
+            ));
+        }
     }
 }
 
-pub fn move_stars(mut stars: Query<&mut Transform, With<Star>>, time: Res<Time>) {
-    let speed = 1.0; // Speed of star movement
-    let field_size = 50.0; // Same as in setup_starfield
+pub fn update_warp(mut warp: ResMut<Warp>, time: Res<Time>) {
+    warp.remaining = (warp.remaining - time.delta_secs()).max(0.0);
+}
 
-    for mut transform in stars.iter_mut() {
-        // Move star from right to left (decrease X)
-        transform.translation.x -= speed * time.delta_secs();
+/// Scroll the star layers at the level's scroll speed, nudged by the ship's movement
+pub fn move_stars(
+    mut stars: Query<(&mut Transform, &Star)>,
+    ships: Query<&Velocity, With<Ship>>,
+    scroll_speed: Res<ScrollSpeed>,
+    warp: Res<Warp>,
+    config: Res<StarfieldConfig>,
+    time: Res<Time>,
+) {
+    let field_size = config.field_size;
+    let ship_nudge = 0.3; // Fraction of the ship's velocity the nearest layer moves against
 
-        // Wrap around: if star goes off the left side, move it to the right side
-        if transform.translation.x < -field_size {
-            transform.translation.x = field_size;
-        }
-    }
-}
+    let ship_velocity = ships
+        .single()
+        .map_or(Vec3::ZERO, |velocity| velocity.linvel);
+    let warp_strength = warp.strength();
+    let speed = scroll_speed.speed * (1.0 + warp_strength * (WARP_SPEED_MULTIPLIER - 1.0));
+
+    for (mut transform, star) in stars.iter_mut() {
+        let layer = &STAR_LAYERS[star.layer];
 
-pub fn rotate_skybox(mut skybox: Query<&mut Transform, With<Skybox>>, time: Res<Time>) {
-    let rotation_speed = 0.001; // Slow rotation speed (radians per second)
+        // Stars move from right to left, and against the ship like the world around it would
+        let velocity = (Vec3::NEG_X * speed - ship_velocity * ship_nudge) * layer.speed_factor;
+        transform.translation.x += velocity.x * time.delta_secs();
+        transform.translation.y += velocity.y * time.delta_secs();
 
-    for mut transform in skybox.iter_mut() {
-        // Rotate around Y axis (vertical axis) for a slow spinning effect
-        transform.rotate_y(rotation_speed * time.delta_secs());
+        // Stretch stars into streaks during a warp
+        transform.scale.x = 1.0 + warp_strength * WARP_STREAK_LENGTH * layer.speed_factor;
+
+        // Wrap around: stars leaving on one side come back on the other
+        if transform.translation.x < -field_size {
+            transform.translation.x += 2.0 * field_size;
+        } else if transform.translation.x > field_size {
+            transform.translation.x -= 2.0 * field_size;
+        }
+        if transform.translation.y < -field_size {
+            transform.translation.y += 2.0 * field_size;
+        } else if transform.translation.y > field_size {
+            transform.translation.y -= 2.0 * field_size;
+        }
     }
 }
 
@@ -89,7 +196,10 @@ pub struct StarfieldPlugin;
 
 impl Plugin for StarfieldPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup_starfield)
-            .add_systems(Update, (move_stars, rotate_skybox));
+        app.init_resource::<StarfieldConfig>()
+            .init_resource::<ScrollSpeed>()
+            .init_resource::<Warp>()
+            .add_systems(Startup, setup_starfield)
+            .add_systems(Update, (update_warp.before(move_stars), move_stars));
     }
 }
@@ -1,3 +1,4 @@
+use crate::fallback::{FallbackShape, SceneFallback};
 use crate::projectiles::spawn_cannon_ball_projectile;
 use crate::weapons::weapon::{Weapon, WeaponMesh};
 use bevy::prelude::*;
@@ -24,15 +25,17 @@ pub fn spawn_cannon_mesh(
     scale: Vec3,
 ) {
     let weapon_mesh_handle = asset_server.load("models/weapons/cannon.glb#Scene0");
+    let rotation_offset = Quat::from_rotation_y(-std::f32::consts::FRAC_PI_2); // Base rotation of the cannon model
     let weapon_mesh_entity = commands
         .spawn((
             Transform {
-                translation, // Position relative to ship
-                rotation: rotation * Quat::from_rotation_y(-std::f32::consts::FRAC_PI_2), // Combine requested rotation with base rotation
+                translation,                          // Position relative to ship
+                rotation: rotation * rotation_offset, // Combine requested rotation with base rotation
                 scale,
             },
-            WeaponMesh,
+            WeaponMesh { rotation_offset },
             SceneRoot(weapon_mesh_handle),
+            SceneFallback(FallbackShape::Cannon),
         ))
         .id();
     // Attach weapon mesh as a child of the parent
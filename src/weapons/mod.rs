@@ -1,4 +1,5 @@
 pub mod cannon;
+pub mod railgun;
 pub mod rocket_launcher;
 pub mod weapon;
 
@@ -0,0 +1,18 @@
+use crate::projectiles::spawn_cannon_ball_projectile;
+use crate::weapons::cannon::spawn_cannon_mesh;
+use crate::weapons::weapon::Weapon;
+use bevy::prelude::*;
+
+/// Creates a railgun weapon with specified position offset
+/// Railgun slugs pierce through several enemies and ricochet off the screen edges
+pub fn create_railgun(weapon_position: Vec3) -> Weapon {
+    Weapon::new()
+        .with_fire_cooldown(0.5) // Slow but powerful
+        .with_projectile_spawner(spawn_cannon_ball_projectile)
+        .with_mesh_spawner(spawn_cannon_mesh)
+        .with_weapon_position_offset(weapon_position)
+        .with_projectile_spawn_offset(Vec3::new(0.15, 0.05, 0.0))
+        .with_projectile_spawn_speed_vector(Vec3::new(25.0, 0.0, 0.0)) // Faster than cannon balls
+        .with_piercing(3) // Goes through 3 enemies before being destroyed
+        .with_ricochet(2) // Bounces twice off the screen edges
+}
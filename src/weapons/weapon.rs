@@ -1,19 +1,21 @@
 use crate::collision::{Collidable, Team};
+use crate::pool::EntityPool;
+use crate::projectiles::{Piercing, ProjectileAssets, Ricochet};
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
-/// Type alias for projectile spawner functions
+/// Type alias for projectile spawner functions, returning the spawned projectile
 pub type ProjectileSpawner = fn(
     &mut Commands,
-    &mut ResMut<Assets<Mesh>>,
-    &mut ResMut<Assets<StandardMaterial>>,
+    &Res<ProjectileAssets>,
+    &mut ResMut<EntityPool>,
     &Res<AssetServer>,
     &mut ResMut<SceneSpawner>,
     Vec3, // position
     Vec3, // velocity
     Quat, // rotation
     Team, // team
-);
+) -> Entity;
 
 /// Type alias for mesh spawner functions
 pub type MeshSpawner = fn(
@@ -26,8 +28,12 @@ pub type MeshSpawner = fn(
     Vec3,   // scale
 );
 
+/// A weapon mesh, child of the entity carrying the weapon.
+/// Its rotation follows the weapon rotation, combined with the mesh's own rotation offset.
 #[derive(Component)]
-pub struct WeaponMesh;
+pub struct WeaponMesh {
+    pub rotation_offset: Quat,
+}
 
 #[derive(Component)]
 pub struct Weapon {
@@ -39,6 +45,11 @@ pub struct Weapon {
     pub projectile_spawn_offset: Vec3, // Offset from weapon position where projectiles spawn
     pub projectile_spawn_speed_vector: Vec3, // Base speed vector for projectiles (before rotation)
     pub weapon_rotation: Quat,        // Rotation of the weapon relative to the ship
+    pub projectile_piercing: u32,     // Number of targets projectiles can pass through
+    pub projectile_ricochets: u32,    // Number of times projectiles can bounce
+    pub burst_size: u32,              // Shots per burst, 0 fires continuously
+    pub burst_pause: f32,             // Cooldown between two bursts
+    pub shots_in_burst: u32,          // Shots fired in the current burst
 }
 
 impl Weapon {
@@ -52,6 +63,11 @@ impl Weapon {
             projectile_spawn_offset: Vec3::ZERO, // Default: spawn at weapon position
             projectile_spawn_speed_vector: Vec3::new(10.0, 0.0, 0.0), // Default: 10 units forward
             weapon_rotation: Quat::IDENTITY,
+            projectile_piercing: 0,
+            projectile_ricochets: 0,
+            burst_size: 0, // Default: no bursts
+            burst_pause: 0.0,
+            shots_in_burst: 0,
         }
     }
 
@@ -85,12 +101,35 @@ impl Weapon {
         self
     }
 
+    pub fn with_piercing(mut self, count: u32) -> Self {
+        self.projectile_piercing = count;
+        self
+    }
+
+    pub fn with_ricochet(mut self, bounces: u32) -> Self {
+        self.projectile_ricochets = bounces;
+        self
+    }
+
+    pub fn with_burst(mut self, shots: u32, pause: f32) -> Self {
+        self.burst_size = shots;
+        self.burst_pause = pause;
+        self
+    }
+
     pub fn can_fire(&self) -> bool {
         self.cooldown_timer <= 0.0
     }
 
     pub fn start_cooldown(&mut self) {
-        self.cooldown_timer = self.fire_cooldown_duration;
+        self.shots_in_burst += 1;
+        if self.burst_size > 0 && self.shots_in_burst >= self.burst_size {
+            // Burst is over, wait longer before the next one
+            self.shots_in_burst = 0;
+            self.cooldown_timer = self.burst_pause;
+        } else {
+            self.cooldown_timer = self.fire_cooldown_duration;
+        }
     }
 }
 
@@ -146,48 +185,98 @@ pub fn fire_weapon(
     velocities: &Query<&Velocity>,
     collidables: &Query<&Collidable>,
     commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &mut ResMut<Assets<StandardMaterial>>,
+    projectile_assets: &Res<ProjectileAssets>,
+    pool: &mut ResMut<EntityPool>,
+    asset_server: &Res<AssetServer>,
+    scene_spawner: &mut ResMut<SceneSpawner>,
+) {
+    // Get owner position, velocity and team
+    if let (Ok(owner_transform), Ok(owner_velocity), Ok(owner_collidable)) = (
+        transforms.get(owner_entity),
+        velocities.get(owner_entity),
+        collidables.get(owner_entity),
+    ) {
+        fire_weapon_from(
+            weapon,
+            owner_transform,
+            owner_velocity.linvel,
+            owner_collidable.team,
+            commands,
+            projectile_assets,
+            pool,
+            asset_server,
+            scene_spawner,
+        );
+    }
+}
+
+/// Fires a weapon from the given world transform, for weapons carried by child entities
+/// whose local Transform is not their position in the world
+pub fn fire_weapon_from(
+    weapon: &mut Weapon,
+    owner_transform: &Transform,
+    owner_velocity: Vec3,
+    team: Team,
+    commands: &mut Commands,
+    projectile_assets: &Res<ProjectileAssets>,
+    pool: &mut ResMut<EntityPool>,
     asset_server: &Res<AssetServer>,
     scene_spawner: &mut ResMut<SceneSpawner>,
 ) {
     // Check if weapon can fire (cooldown has passed)
     if weapon.can_fire() {
-        // Get owner position, velocity and team
-        if let (Ok(owner_transform), Ok(owner_velocity), Ok(owner_collidable)) = (
-            transforms.get(owner_entity),
-            velocities.get(owner_entity),
-            collidables.get(owner_entity),
-        ) {
-            // Calculate projectile velocity: ship velocity + weapon's spawn speed vector (rotated with ship and weapon)
-            let combined_rotation = owner_transform.rotation * weapon.weapon_rotation;
-            let forward_direction = combined_rotation * weapon.projectile_spawn_speed_vector;
-            let projectile_velocity = owner_velocity.linvel + forward_direction;
-
-            // Calculate weapon position and projectile spawn position (rotated with ship)
-            let rotated_weapon_offset = owner_transform.rotation * weapon.weapon_position_offset;
-            let rotated_projectile_offset = combined_rotation * weapon.projectile_spawn_offset;
-
-            let weapon_position = owner_transform.translation + rotated_weapon_offset;
-            let projectile_position = weapon_position + rotated_projectile_offset;
-
-            // Spawn projectile using the weapon's projectile spawner
-            if let Some(spawner) = weapon.projectile_spawner {
-                spawner(
-                    commands,
-                    meshes,
-                    materials,
-                    asset_server,
-                    scene_spawner,
-                    projectile_position,
-                    projectile_velocity,
-                    combined_rotation,
-                    owner_collidable.team,
-                );
+        // Calculate projectile velocity: ship velocity + weapon's spawn speed vector (rotated with ship and weapon)
+        let combined_rotation = owner_transform.rotation * weapon.weapon_rotation;
+        let forward_direction = combined_rotation * weapon.projectile_spawn_speed_vector;
+        let projectile_velocity = owner_velocity + forward_direction;
+
+        // Calculate weapon position and projectile spawn position (rotated with ship)
+        let rotated_weapon_offset = owner_transform.rotation * weapon.weapon_position_offset;
+        let rotated_projectile_offset = combined_rotation * weapon.projectile_spawn_offset;
+
+        let weapon_position = owner_transform.translation + rotated_weapon_offset;
+        let projectile_position = weapon_position + rotated_projectile_offset;
+
+        // Spawn projectile using the weapon's projectile spawner
+        if let Some(spawner) = weapon.projectile_spawner {
+            let projectile_entity = spawner(
+                commands,
+                projectile_assets,
+                pool,
+                asset_server,
+                scene_spawner,
+                projectile_position,
+                projectile_velocity,
+                combined_rotation,
+                team,
+            );
+
+            // Apply the weapon's projectile modifiers
+            if weapon.projectile_piercing > 0 {
+                commands
+                    .entity(projectile_entity)
+                    .insert(Piercing::new(weapon.projectile_piercing));
             }
+            if weapon.projectile_ricochets > 0 {
+                commands
+                    .entity(projectile_entity)
+                    .insert(Ricochet::new(weapon.projectile_ricochets));
+            }
+        }
 
-            // Start cooldown
-            weapon.start_cooldown();
+        // Start cooldown
+        weapon.start_cooldown();
+    }
+}
+
+/// Keep weapon meshes pointing where their weapon aims
+pub fn sync_weapon_meshes(
+    weapons: Query<&Weapon>,
+    mut weapon_meshes: Query<(&WeaponMesh, &ChildOf, &mut Transform)>,
+) {
+    for (weapon_mesh, child_of, mut transform) in weapon_meshes.iter_mut() {
+        if let Ok(weapon) = weapons.get(child_of.parent()) {
+            transform.rotation = weapon.weapon_rotation * weapon_mesh.rotation_offset;
         }
     }
 }
@@ -196,6 +285,6 @@ pub struct WeaponPlugin;
 
 impl Plugin for WeaponPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, update_weapon_cooldowns);
+        app.add_systems(Update, (update_weapon_cooldowns, sync_weapon_meshes));
     }
 }